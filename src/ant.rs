@@ -1,12 +1,27 @@
 use bevy::{ecs::batching::BatchingStrategy, prelude::*, window::PrimaryWindow};
+use rand::Rng;
 
 use crate::{
+    bindings::{Action, Actions},
     camera::{FocusableEntity, FocusedEntity},
-    grid::{Grid, GridEntity},
-    utils::{window_to_world, ViewCone},
-    ANT_COUNT, ANT_ROTATION_SPEED, ANT_SIZE, ANT_SPEED, ANT_VIEW_ANGLE, ANT_VIEW_DISTANCE,
-    DEBUG_ANT_VIEW_COLOR, DEBUG_ANT_VIEW_COLOR_ALERT, DEBUG_ANT_VIEW_RADIUS_COLOR, NEST_COLOR,
-    NEST_POSITION, NEST_SIZE, PHEROMONE_DECAY,
+    caste::{Caste, CasteProfile, CasteSettings},
+    colony::{Colonies, Colony, ColonyId},
+    food::FoodSource,
+    genome::Genome,
+    grid::{Grid, GridEntity, GridEntityType, Topology},
+    pheromone::{PheromoneMap, PheromoneSettings, Pheromones},
+    predator::Predator,
+    rng::SimulationRng,
+    tools::{ActiveTool, Tool},
+    ui::{PointerOverUi, UiControls},
+    utils::{cursor_to_world, ViewCone},
+    ANT_ALARM_DEPOSIT, ANT_ALARM_THRESHOLD, ANT_CONTACT_DISTANCE, ANT_ENERGY, ANT_FIGHT_DAMAGE,
+    ANT_MIN_VISIBLE_FRACTION, ANT_ROTATION_SPEED, ANT_SCENT_FALLOFF, ANT_SCENT_RADIUS,
+    ANT_SCENT_THRESHOLD, ANT_SCENT_WEIGHT, ANT_SENSOR_ANGLE, ANT_SENSOR_DISTANCE,
+    ANT_SEPARATION_DISTANCE, ANT_SEPARATION_WEIGHT, ANT_SIZE, ANT_SPAWN_COST, ANT_SPEED,
+    ANT_VIEW_ANGLE, ANT_VIEW_DISTANCE, COLONY_MAX_POPULATION, DEBUG_ANT_VIEW_COLOR,
+    DEBUG_ANT_VIEW_COLOR_ALERT, DEBUG_ANT_VIEW_RADIUS_COLOR, FOOD_COLOR, NEST_SIZE,
+    PHEROMONE_FOLLOW_THRESHOLD, PREDATOR_SIZE, SPRITE_ANT,
 };
 
 pub struct AntPlugin;
@@ -14,23 +29,42 @@ pub struct AntPlugin;
 impl Plugin for AntPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<AntSettings>()
+            .init_resource::<CasteSettings>()
             .add_systems(Startup, spawn_ants)
-            .add_systems(Update, move_ants)
-            .add_systems(Update, check_mouse)
+            .add_systems(Update, spawn_from_nests)
+            .add_systems(Update, move_ants.after(check_mouse))
+            .add_systems(Update, check_mouse.after(ant_react_to_danger))
+            .add_systems(
+                Update,
+                ant_react_to_danger
+                    .after(ant_follow_pheromones)
+                    .after(ant_sees_predators),
+            )
             .add_systems(Update, ant_focused)
-            .add_systems(Update, draw_nest)
-            .add_systems(Update, ant_sees_other_ant);
+            .add_systems(Update, ant_sees_other_ant)
+            .add_systems(Update, ant_sees_predators)
+            .add_systems(Update, ant_smell.before(ant_forage))
+            .add_systems(Update, ant_antennation.after(ant_sees_other_ant))
+            .add_systems(Update, ant_fight.after(ant_sees_other_ant))
+            .add_systems(Update, ant_forage.after(ant_antennation))
+            .add_systems(Update, ant_follow_pheromones.after(ant_forage))
+            .add_systems(Update, ant_deposit_pheromones.after(move_ants));
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Debug, Clone)]
 pub struct AntSettings {
     pub view_distance: f32,
     pub view_angle: f32,
     pub speed: f32,
-    pub n_ants: usize,
+    /// Scales the pheromone levels an ant smells
+    pub pheromone_sensitivity: f32,
     pub nest_size: f32,
-    pub nest_position: Vec2,
+    pub scent_radius: f32,
+    /// How fast a scent fades with distance, higher smells mostly what is close
+    pub scent_falloff: f32,
+    /// Share of the scent direction when blended with what the ant sees
+    pub scent_weight: f32,
 }
 
 impl Default for AntSettings {
@@ -39,36 +73,92 @@ impl Default for AntSettings {
             view_distance: ANT_VIEW_DISTANCE,
             view_angle: ANT_VIEW_ANGLE,
             speed: ANT_SPEED,
-            n_ants: ANT_COUNT,
+            pheromone_sensitivity: 1.0,
             nest_size: NEST_SIZE,
-            nest_position: NEST_POSITION.into(),
+            scent_radius: ANT_SCENT_RADIUS,
+            scent_falloff: ANT_SCENT_FALLOFF,
+            scent_weight: ANT_SCENT_WEIGHT,
         }
     }
 }
 
-#[derive(Debug)]
+impl AntSettings {
+    /// Settings as seen by an ant of the given caste
+    pub fn with_caste(&self, profile: &CasteProfile) -> Self {
+        Self {
+            view_distance: self.view_distance * profile.view_distance,
+            view_angle: self.view_angle * profile.view_angle,
+            speed: self.speed * profile.speed,
+            pheromone_sensitivity: self.pheromone_sensitivity * profile.pheromone_sensitivity,
+            ..self.clone()
+        }
+    }
+
+    /// Strength of a scent smelled `distance` away, from 1 on the spot to 0 out of range
+    pub fn scent_strength(&self, distance: f32) -> f32 {
+        if distance >= self.scent_radius {
+            return 0.0;
+        }
+        (1.0 - distance / self.scent_radius).powf(self.scent_falloff)
+    }
+
+    /// Blend a direction from vision with a scent gradient, following either alone
+    pub fn blend_scent(&self, vision: Vec2, scent: Vec2) -> Vec2 {
+        let scent = scent.normalize_or_zero();
+        if vision == Vec2::ZERO {
+            return scent;
+        }
+        (vision.normalize_or_zero() * (1.0 - self.scent_weight) + scent * self.scent_weight)
+            .normalize_or_zero()
+    }
+
+    /// Settings as seen by an ant with the given genome
+    pub fn with_genome(&self, genome: &Genome) -> Self {
+        Self {
+            view_angle: (self.view_angle * genome.view_angle).min(std::f32::consts::TAU),
+            pheromone_sensitivity: self.pheromone_sensitivity * genome.trail_follow_weight,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DesiredTarget {
     PHEROMONE,
     FOOD,
+    NEST,
     NOTHING,
+    /// Fleeing a predator, or charging it for soldiers
+    DANGER,
 }
 
-pub enum Pheromones {
-    LookingForFood,
-    LookingForHome,
-}
+impl DesiredTarget {
+    pub const ALL: [DesiredTarget; 5] = [
+        DesiredTarget::PHEROMONE,
+        DesiredTarget::FOOD,
+        DesiredTarget::NEST,
+        DesiredTarget::NOTHING,
+        DesiredTarget::DANGER,
+    ];
 
-impl Pheromones {
-    pub fn decay(&self, pheromone: f32) -> f32 {
+    pub fn index(&self) -> usize {
         match self {
-            _ => pheromone * PHEROMONE_DECAY,
+            DesiredTarget::PHEROMONE => 0,
+            DesiredTarget::FOOD => 1,
+            DesiredTarget::NEST => 2,
+            DesiredTarget::NOTHING => 3,
+            DesiredTarget::DANGER => 4,
         }
     }
 
-    pub fn get_color(&self) -> [f32; 4] {
+    /// Colour the state is shown with in charts and trails
+    pub fn color(&self) -> [f32; 4] {
         match self {
-            Pheromones::LookingForFood => [1.0, 0.0, 0.0, 1.0],
-            Pheromones::LookingForHome => [0.0, 0.0, 1.0, 1.0],
+            DesiredTarget::PHEROMONE => [0.8, 0.2, 0.8, 1.0],
+            DesiredTarget::FOOD => FOOD_COLOR,
+            DesiredTarget::NEST => [1.0, 0.9, 0.2, 1.0],
+            DesiredTarget::NOTHING => [0.6, 0.6, 0.6, 1.0],
+            DesiredTarget::DANGER => [1.0, 0.3, 0.2, 1.0],
         }
     }
 }
@@ -77,6 +167,18 @@ impl Pheromones {
 pub struct Ant {
     desired_direction: Vec2,
     desired_target: DesiredTarget,
+    /// Last place the ant knows food was found, learned or shared by antennation
+    known_food: Option<Vec2>,
+    /// Food taken from a source and not yet brought back to the nest
+    carrying: f32,
+    /// Drained by fights, the ant dies when it runs out
+    energy: f32,
+}
+
+impl Default for Ant {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Ant {
@@ -84,16 +186,81 @@ impl Ant {
         Self {
             desired_direction: Vec2::ZERO,
             desired_target: DesiredTarget::NOTHING,
+            known_food: None,
+            carrying: 0.0,
+            energy: ANT_ENERGY,
+        }
+    }
+
+    pub fn desired_direction(&self) -> Vec2 {
+        self.desired_direction
+    }
+
+    /// Where the ant believes food is, if it knows any
+    pub fn known_food(&self) -> Option<Vec2> {
+        self.known_food
+    }
+
+    pub fn desired_target(&self) -> DesiredTarget {
+        self.desired_target
+    }
+
+    pub fn carrying(&self) -> f32 {
+        self.carrying
+    }
+
+    pub fn energy(&self) -> f32 {
+        self.energy
+    }
+
+    /// Override the current goal, a wandering ant also forgets where food is
+    pub fn force_target(&mut self, target: DesiredTarget) {
+        self.desired_target = target;
+        if target == DesiredTarget::NOTHING {
+            self.known_food = None;
         }
     }
 
-    pub fn get_view_cone(&self, transform: &Transform, settings: &AntSettings) -> ViewCone {
-        ViewCone::new(
+    /// Pheromone the ant lays where it walks
+    pub fn trail_pheromone(&self) -> Pheromones {
+        if self.carrying > 0.0 {
+            Pheromones::LookingForHome
+        } else {
+            Pheromones::LookingForFood
+        }
+    }
+
+    /// Pheromone the ant follows to reach its next goal
+    pub fn followed_pheromone(&self) -> Pheromones {
+        if self.carrying > 0.0 {
+            Pheromones::LookingForFood
+        } else {
+            Pheromones::LookingForHome
+        }
+    }
+
+    /// Positions the ant smells pheromones at: left, ahead and right
+    pub fn sensor_positions(&self, transform: &Transform) -> [Vec2; 3] {
+        let position = transform.translation.truncate();
+        let forward = (transform.rotation * Vec3::Y).truncate();
+        [ANT_SENSOR_ANGLE, 0.0, -ANT_SENSOR_ANGLE]
+            .map(|angle| position + Vec2::from_angle(angle).rotate(forward) * ANT_SENSOR_DISTANCE)
+    }
+
+    pub fn get_view_cone(
+        &self,
+        transform: &Transform,
+        settings: &AntSettings,
+        grid: &Grid,
+    ) -> ViewCone {
+        let mut view_cone = ViewCone::new(
             transform.translation.truncate(),
             settings.view_distance,
             settings.view_angle,
             transform.rotation.to_euler(EulerRot::XYZ).2,
-        )
+        );
+        view_cone.wrap_around(grid.wrap_size());
+        view_cone
     }
 
     pub fn debug_view(
@@ -137,89 +304,661 @@ impl Ant {
     }
 }
 
+fn spawn_ant(
+    commands: &mut Commands,
+    texture_handle: &Handle<Image>,
+    grid: &Grid,
+    (colony_id, colony): (ColonyId, &Colony),
+    caste: Caste,
+    castes: &CasteSettings,
+    genome: Genome,
+    translation: Vec3,
+    rng: &mut SimulationRng,
+) {
+    let rotation = Quat::from_rotation_z(rng.gen::<f32>() * std::f32::consts::TAU);
+    commands.spawn((
+        Sprite {
+            image: texture_handle.clone(),
+            color: LinearRgba::from_f32_array(colony.color).into(),
+            ..Default::default()
+        },
+        Transform {
+            translation,
+            rotation,
+            scale: Vec3::splat(castes.profile(caste).size),
+        },
+        Ant::new(),
+        caste,
+        genome,
+        colony_id,
+        AntPerception::default(),
+        FocusableEntity::default(),
+        GridEntity::new(
+            grid.get_grid_pos(translation.truncate()),
+            GridEntityType::Ant,
+        ),
+    ));
+}
+
 fn spawn_ants(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    grid: ResMut<Grid>,
+    grid: Res<Grid>,
     ant_settings: Res<AntSettings>,
+    colonies: Res<Colonies>,
+    castes: Res<CasteSettings>,
+    mut rng: ResMut<SimulationRng>,
 ) {
-    let texture_handle = asset_server.load("ant.png");
-
-    (0..ant_settings.n_ants).into_iter().for_each(|_| {
-        let angle = rand::random::<f32>() * std::f32::consts::TAU;
-        let distance = rand::random::<f32>() * NEST_SIZE * 1.6;
-        let translation = Vec3::new(
-            NEST_POSITION.0 + distance * angle.cos(),
-            NEST_POSITION.1 + distance * angle.sin(),
-            0.1,
-        );
-        let rotation = Quat::from_rotation_z(rand::random::<f32>() * std::f32::consts::TAU);
-        commands.spawn((
-            Sprite {
-                image: texture_handle.clone(),
-                ..Default::default()
-            },
-            Transform {
+    let texture_handle = asset_server.load(SPRITE_ANT);
+
+    colonies.iter().for_each(|(colony_id, colony)| {
+        let mut caste_counts = [0; 3];
+        (0..colony.n_ants).for_each(|_| {
+            let angle = rng.gen::<f32>() * std::f32::consts::TAU;
+            let distance = rng.gen::<f32>() * ant_settings.nest_size * 1.6;
+            let translation =
+                (colony.nest_position + Vec2::from_angle(angle) * distance).extend(0.1);
+            let caste = castes.next_caste(caste_counts);
+            caste_counts[caste.index()] += 1;
+            spawn_ant(
+                &mut commands,
+                &texture_handle,
+                &grid,
+                (colony_id, colony),
+                caste,
+                &castes,
+                colony.gene_pool.offspring(&mut *rng),
                 translation,
-                rotation,
-                ..Default::default()
-            },
-            Ant::new(),
-            FocusableEntity::default(),
-            GridEntity::new(grid.get_grid_pos(translation.truncate())),
-        ));
+                &mut rng,
+            );
+        });
     });
 }
 
+/// Nests turn stored food into new ants, picking the caste they lack the most
+/// and breeding them from the genomes of successful foragers
+fn spawn_from_nests(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    grid: Res<Grid>,
+    mut colonies: ResMut<Colonies>,
+    castes: Res<CasteSettings>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    let texture_handle = asset_server.load(SPRITE_ANT);
+
+    for (i, colony) in colonies.0.iter_mut().enumerate() {
+        if colony.food_stored < ANT_SPAWN_COST || colony.population >= COLONY_MAX_POPULATION {
+            continue;
+        }
+        colony.food_stored -= ANT_SPAWN_COST;
+        colony.population += 1;
+
+        let caste = castes.next_caste(colony.caste_population);
+        colony.caste_population[caste.index()] += 1;
+        spawn_ant(
+            &mut commands,
+            &texture_handle,
+            &grid,
+            (ColonyId(i), colony),
+            caste,
+            &castes,
+            colony.gene_pool.offspring(&mut *rng),
+            colony.nest_position.extend(0.1),
+            &mut rng,
+        );
+    }
+}
+
+/// What an ant currently perceives of the other ants around it, and smells all around
+#[derive(Debug, Default, Component)]
+pub struct AntPerception {
+    /// Ants inside the view cone, refreshed every frame
+    pub visible_ants: Vec<Entity>,
+    /// Push away from visible ants that are too close
+    pub separation: Vec2,
+    /// Toward the food nearby, longer the stronger the smell
+    pub food_scent: Vec2,
+    /// Toward the pheromone the ant follows, longer the stronger the smell
+    pub pheromone_scent: Vec2,
+    /// Toward the alarm of its colony, longer the stronger the smell
+    pub alarm_scent: Vec2,
+    /// Closest predator in sight
+    pub predator: Option<Vec2>,
+}
+
 fn ant_sees_other_ant(
-    ants: Query<(&Transform, Entity, &Ant, &GridEntity), With<Ant>>,
+    mut ants: Query<(
+        &Transform,
+        Entity,
+        &Ant,
+        &Caste,
+        &Genome,
+        &mut AntPerception,
+    )>,
+    others: Query<&Transform, With<Ant>>,
     ants_settings: Res<AntSettings>,
+    castes: Res<CasteSettings>,
     grid: Res<Grid>,
 ) {
-    ants.par_iter()
+    ants.par_iter_mut()
         .batching_strategy(BatchingStrategy {
             batch_size_limits: 1..500,
             ..Default::default()
         })
-        .for_each(|(ant_transform, ant_entity, ant, _)| {
-            let ant_position = ant_transform.translation.truncate();
-            let cells_in_area =
-                grid.get_cells_in_area_from_world(ant_position, ants_settings.view_distance);
-            let _view_cone = ant.get_view_cone(ant_transform, &ants_settings);
+        .for_each(
+            |(ant_transform, ant_entity, ant, caste, genome, mut perception)| {
+                let ants_settings = ants_settings
+                    .with_caste(castes.profile(*caste))
+                    .with_genome(genome);
+                let ant_position = ant_transform.translation.truncate();
+                let cells_in_area =
+                    grid.get_cells_in_area_from_world(ant_position, ants_settings.view_distance);
+                let view_cone = ant.get_view_cone(ant_transform, &ants_settings, &grid);
 
-            cells_in_area
-                .into_iter()
-                .for_each(|(_, (ants_grid_entities, _, _))| {
-                    ants_grid_entities.into_iter().for_each(|(_, entitie)| {
-                        if entitie != ant_entity {
-                            if let Ok((other_ant_transform, _, _, _)) = ants.get(entitie) {
-                                let _other_ant_position =
-                                    other_ant_transform.translation.truncate();
-                                // if view_cone.contains(other_ant_position, ANT_SIZE / 2.) {
-                                //     // Ant sees another ant
-                                // }
+                let mut in_view = Vec::new();
+                let mut separation = Vec2::ZERO;
+                cells_in_area
+                    .into_iter()
+                    .for_each(|(_, (ants_grid_entities, _, _))| {
+                        ants_grid_entities.into_iter().for_each(|(_, entitie)| {
+                            if entitie != ant_entity {
+                                if let Ok(other_ant_transform) = others.get(entitie) {
+                                    let other_ant_position =
+                                        other_ant_transform.translation.truncate();
+                                    if view_cone.contains(other_ant_position, ANT_SIZE / 2.) {
+                                        in_view.push((entitie, other_ant_position));
+
+                                        // The closer the other ant, the harder to push away
+                                        let away = grid.delta(other_ant_position, ant_position);
+                                        let distance = away.length();
+                                        if distance > 0.0 && distance < ANT_SEPARATION_DISTANCE {
+                                            separation += away / distance
+                                                * (1.0 - distance / ANT_SEPARATION_DISTANCE);
+                                        }
+                                    }
+                                }
                             }
+                        })
+                    });
+
+                // Closest first, an ant can only be hidden by nearer ones
+                in_view.sort_by(|a, b| {
+                    grid.distance(ant_position, a.1)
+                        .total_cmp(&grid.distance(ant_position, b.1))
+                });
+                perception.visible_ants = in_view
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, (_, position))| {
+                        view_cone.visible_fraction(*position, ANT_SIZE / 2.)
+                            >= ANT_MIN_VISIBLE_FRACTION
+                            && grid.line_of_sight(ant_position, *position)
+                            && !in_view[..*index].iter().any(|(_, blocker)| {
+                                view_cone.occludes(*blocker, ANT_SIZE / 2., *position)
+                            })
+                    })
+                    .map(|(_, (entity, _))| *entity)
+                    .collect();
+                perception.separation = separation;
+            },
+        );
+}
+
+/// Ants smell food, their trail pheromone and alarm all around them, stronger when closer
+fn ant_smell(
+    mut ants: Query<(&Transform, &Ant, &ColonyId, &mut AntPerception)>,
+    food_sources: Query<(&Transform, &FoodSource)>,
+    pheromone_map: Res<PheromoneMap>,
+    ants_settings: Res<AntSettings>,
+    grid: Res<Grid>,
+) {
+    let radius = ants_settings.scent_radius;
+    ants.par_iter_mut()
+        .batching_strategy(BatchingStrategy {
+            batch_size_limits: 1..500,
+            ..Default::default()
+        })
+        .for_each(|(transform, ant, colony_id, mut perception)| {
+            let ant_position = transform.translation.truncate();
+            // Direction to a source weighted by how strongly it is smelled
+            let gradient = |position: Vec2, amount: f32| {
+                let offset = grid.delta(ant_position, position);
+                let distance = offset.length();
+                if distance <= 0.0 {
+                    return Vec2::ZERO;
+                }
+                offset / distance * amount * ants_settings.scent_strength(distance)
+            };
+
+            perception.food_scent = grid
+                .entities_near(GridEntityType::Food, ant_position, radius)
+                .filter_map(|entity| food_sources.get(entity).ok())
+                .filter(|(_, food)| food.amount > 0.0)
+                .map(|(food_transform, _)| gradient(food_transform.translation.truncate(), 1.0))
+                .sum();
+
+            let pheromone = ant.followed_pheromone();
+            let (pheromone_scent, alarm_scent) = grid
+                .get_cells_in_area_from_grid(grid.get_grid_pos(ant_position), radius)
+                .into_iter()
+                .map(|cell| {
+                    let smell = |pheromone| {
+                        let level = pheromone_map.get(*colony_id, pheromone, cell);
+                        gradient(grid.get_world_pos(cell), level)
+                    };
+                    (smell(pheromone), smell(Pheromones::Alarm))
+                })
+                .fold(
+                    (Vec2::ZERO, Vec2::ZERO),
+                    |(trail, alarm), (cell_trail, cell_alarm)| {
+                        (trail + cell_trail, alarm + cell_alarm)
+                    },
+                );
+            perception.pheromone_scent = pheromone_scent;
+            perception.alarm_scent = alarm_scent;
+        });
+}
+
+/// Ants of a colony touching antennae tell each other where they found food
+fn ant_antennation(
+    mut ants: Query<(Entity, &Transform, &mut Ant, &ColonyId, &AntPerception)>,
+    grid: Res<Grid>,
+) {
+    let mut shared_food = Vec::new();
+    for (ant_entity, transform, ant, colony_id, perception) in ants.iter() {
+        if ant.known_food.is_some() {
+            continue;
+        }
+        let ant_position = transform.translation.truncate();
+        let food = perception.visible_ants.iter().find_map(|&other| {
+            let (_, other_transform, other_ant, other_colony, _) = ants.get(other).ok()?;
+            let other_position = other_transform.translation.truncate();
+            if other_colony != colony_id
+                || grid.distance(ant_position, other_position) > ANT_CONTACT_DISTANCE
+            {
+                return None;
+            }
+            other_ant.known_food
+        });
+        if let Some(food) = food {
+            shared_food.push((ant_entity, food));
+        }
+    }
+
+    for (ant_entity, food) in shared_food {
+        if let Ok((_, transform, mut ant, _, _)) = ants.get_mut(ant_entity) {
+            ant.known_food = Some(food);
+            // A wandering ant heads for the food it just heard about
+            if let DesiredTarget::NOTHING = ant.desired_target {
+                ant.desired_direction = grid
+                    .delta(transform.translation.truncate(), food)
+                    .normalize_or_zero();
+            }
+        }
+    }
+}
+
+/// Ants pick up the food they see and bring it back to their nest
+fn ant_forage(
+    mut ants: Query<(
+        &Transform,
+        &mut Ant,
+        &ColonyId,
+        &Caste,
+        &Genome,
+        &AntPerception,
+    )>,
+    mut food_sources: Query<(&Transform, &mut FoodSource), Without<Ant>>,
+    mut colonies: ResMut<Colonies>,
+    ants_settings: Res<AntSettings>,
+    castes: Res<CasteSettings>,
+    grid: Res<Grid>,
+) {
+    for (transform, mut ant, colony_id, caste, genome, perception) in ants.iter_mut() {
+        let profile = castes.profile(*caste);
+        if profile.carrying_capacity <= 0.0 {
+            continue; // This caste leaves the food alone
+        }
+        let ants_settings = ants_settings.with_caste(profile).with_genome(genome);
+        let ant_position = transform.translation.truncate();
+        let forward = (transform.rotation * Vec3::Y).truncate();
+        let view_cone = ant.get_view_cone(transform, &ants_settings, &grid);
+        let colony = colonies.get_mut(*colony_id);
+
+        if ant.carrying > 0.0 {
+            let to_nest = grid.delta(ant_position, colony.nest_position);
+            if to_nest.length() <= ants_settings.nest_size {
+                // Drop the food and head back to where it was found
+                colony.food_stored += ant.carrying;
+                colony.gene_pool.add(genome);
+                ant.carrying = 0.0;
+                ant.desired_target = DesiredTarget::NOTHING;
+                ant.desired_direction = match ant.known_food {
+                    Some(food) => grid.delta(ant_position, food).normalize_or_zero(),
+                    None => -forward,
+                };
+            } else if view_cone.contains(colony.nest_position, ants_settings.nest_size)
+                && grid.line_of_sight(ant_position, colony.nest_position)
+            {
+                ant.desired_target = DesiredTarget::NEST;
+                ant.desired_direction = to_nest.normalize_or_zero();
+            }
+            continue;
+        }
+
+        // Closest food source in sight
+        let nearest_food = grid
+            .get_cells_in_area_from_world(ant_position, ants_settings.view_distance)
+            .into_iter()
+            .flat_map(|(_, (_, food_grid_entities, _))| food_grid_entities)
+            .filter_map(|(_, food_entity)| {
+                let (food_transform, food) = food_sources.get(food_entity).ok()?;
+                let food_position = food_transform.translation.truncate();
+                (food.amount > 0.0
+                    && view_cone.contains(food_position, food.radius())
+                    && grid.line_of_sight(ant_position, food_position))
+                .then(|| (food_entity, food_position, food.radius()))
+            })
+            .min_by(|a, b| {
+                grid.distance(ant_position, a.1)
+                    .total_cmp(&grid.distance(ant_position, b.1))
+            });
+
+        match nearest_food {
+            Some((food_entity, food_position, food_radius)) => {
+                ant.known_food = Some(food_position);
+                let to_food = grid.delta(ant_position, food_position);
+                if to_food.length() <= food_radius + ANT_CONTACT_DISTANCE / 2.0 {
+                    if let Ok((_, mut food)) = food_sources.get_mut(food_entity) {
+                        ant.carrying = food.take(profile.carrying_capacity);
+                    }
+                    // Turn around and look for the way home
+                    ant.desired_target = DesiredTarget::NOTHING;
+                    ant.desired_direction = -forward;
+                } else {
+                    ant.desired_target = DesiredTarget::FOOD;
+                    ant.desired_direction =
+                        ants_settings.blend_scent(to_food, perception.food_scent);
+                }
+            }
+            None => {
+                // The known food is in sight but gone
+                if let Some(food) = ant.known_food {
+                    if view_cone.contains(food, 0.0) && grid.line_of_sight(ant_position, food) {
+                        ant.known_food = None;
+                    }
+                }
+                // Keep heading to remembered food until it is found gone
+                if let DesiredTarget::FOOD = ant.desired_target {
+                    match ant.known_food {
+                        Some(food) => {
+                            ant.desired_direction = ants_settings
+                                .blend_scent(grid.delta(ant_position, food), perception.food_scent);
                         }
+                        None => ant.desired_target = DesiredTarget::NOTHING,
+                    }
+                }
+                // A wandering ant drifts toward the food it smells
+                if let DesiredTarget::NOTHING = ant.desired_target {
+                    if perception.food_scent.length() > ANT_SCENT_THRESHOLD {
+                        ant.desired_direction =
+                            ants_settings.blend_scent(ant.desired_direction, perception.food_scent);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Ants without a goal in sight steer towards the strongest pheromone of their colony
+fn ant_follow_pheromones(
+    mut ants: Query<(
+        &Transform,
+        &mut Ant,
+        &ColonyId,
+        &Caste,
+        &Genome,
+        &AntPerception,
+    )>,
+    pheromone_map: Res<PheromoneMap>,
+    ants_settings: Res<AntSettings>,
+    castes: Res<CasteSettings>,
+    grid: Res<Grid>,
+) {
+    ants.par_iter_mut()
+        .batching_strategy(BatchingStrategy {
+            batch_size_limits: 1..500,
+            ..Default::default()
+        })
+        .for_each(
+            |(transform, mut ant, colony_id, caste, genome, perception)| {
+                let sensitivity = ants_settings
+                    .with_caste(castes.profile(*caste))
+                    .with_genome(genome)
+                    .pheromone_sensitivity;
+                if !matches!(
+                    ant.desired_target,
+                    DesiredTarget::NOTHING | DesiredTarget::PHEROMONE
+                ) {
+                    return;
+                }
+
+                let ant_position = transform.translation.truncate();
+                let pheromone = ant.followed_pheromone();
+                let strongest = ant
+                    .sensor_positions(transform)
+                    .into_iter()
+                    .map(|sensor| {
+                        let level = pheromone_map.sample(&grid, *colony_id, pheromone, sensor);
+                        (sensor, level * sensitivity)
                     })
+                    .max_by(|a, b| a.1.total_cmp(&b.1));
+
+                // The antennae give the direction, the smell all around steers it
+                let scent = perception.pheromone_scent * sensitivity;
+                match strongest {
+                    Some((sensor, level)) if level > PHEROMONE_FOLLOW_THRESHOLD => {
+                        ant.desired_target = DesiredTarget::PHEROMONE;
+                        ant.desired_direction =
+                            ants_settings.blend_scent(grid.delta(ant_position, sensor), scent);
+                    }
+                    _ if scent.length() > ANT_SCENT_THRESHOLD => {
+                        ant.desired_target = DesiredTarget::PHEROMONE;
+                        ant.desired_direction = scent.normalize_or_zero();
+                    }
+                    _ => {
+                        if let DesiredTarget::PHEROMONE = ant.desired_target {
+                            ant.desired_target = DesiredTarget::NOTHING;
+                        }
+                    }
+                }
+            },
+        );
+}
+
+/// Ants spot the predators in their view cone
+fn ant_sees_predators(
+    mut ants: Query<(&Transform, &Ant, &Caste, &Genome, &mut AntPerception)>,
+    predators: Query<&Transform, With<Predator>>,
+    ants_settings: Res<AntSettings>,
+    castes: Res<CasteSettings>,
+    grid: Res<Grid>,
+) {
+    ants.par_iter_mut()
+        .batching_strategy(BatchingStrategy {
+            batch_size_limits: 1..500,
+            ..Default::default()
+        })
+        .for_each(|(transform, ant, caste, genome, mut perception)| {
+            let ants_settings = ants_settings
+                .with_caste(castes.profile(*caste))
+                .with_genome(genome);
+            let ant_position = transform.translation.truncate();
+            let view_cone = ant.get_view_cone(transform, &ants_settings, &grid);
+            perception.predator = grid
+                .entities_near(
+                    GridEntityType::Predator,
+                    ant_position,
+                    ants_settings.view_distance,
+                )
+                .filter_map(|entity| {
+                    let position = predators.get(entity).ok()?.translation.truncate();
+                    (view_cone.contains(position, PREDATOR_SIZE / 2.0)
+                        && grid.line_of_sight(ant_position, position))
+                    .then_some(position)
+                })
+                .min_by(|a, b| {
+                    grid.distance(ant_position, *a)
+                        .total_cmp(&grid.distance(ant_position, *b))
                 });
         });
 }
 
+/// Ants run from the predators they see or the alarm they smell, soldiers charge instead
+fn ant_react_to_danger(
+    mut ants: Query<(&Transform, &mut Ant, &Caste, &AntPerception)>,
+    castes: Res<CasteSettings>,
+    grid: Res<Grid>,
+) {
+    ants.par_iter_mut()
+        .batching_strategy(BatchingStrategy {
+            batch_size_limits: 1..500,
+            ..Default::default()
+        })
+        .for_each(|(transform, mut ant, caste, perception)| {
+            let ant_position = transform.translation.truncate();
+            let danger = match perception.predator {
+                Some(predator) => Some(grid.delta(ant_position, predator)),
+                None if perception.alarm_scent.length() > ANT_ALARM_THRESHOLD => {
+                    Some(perception.alarm_scent)
+                }
+                None => None,
+            };
+            match danger {
+                Some(danger) => {
+                    ant.desired_target = DesiredTarget::DANGER;
+                    ant.desired_direction = if castes.profile(*caste).defends {
+                        danger.normalize_or_zero()
+                    } else {
+                        -danger.normalize_or_zero()
+                    };
+                }
+                None => {
+                    if let DesiredTarget::DANGER = ant.desired_target {
+                        ant.desired_target = DesiredTarget::NOTHING;
+                    }
+                }
+            }
+        });
+}
+
+fn ant_deposit_pheromones(
+    ants: Query<(&Transform, &Ant, &ColonyId, &Caste, &Genome, &AntPerception)>,
+    mut pheromone_map: ResMut<PheromoneMap>,
+    settings: Res<PheromoneSettings>,
+    castes: Res<CasteSettings>,
+    time: Res<Time>,
+    grid: Res<Grid>,
+) {
+    let amount = settings.deposit * time.delta_secs();
+    for (transform, ant, colony_id, caste, genome, perception) in ants.iter() {
+        // Every caste raises the alarm when it sees a predator
+        if perception.predator.is_some() {
+            pheromone_map.deposit(
+                *colony_id,
+                Pheromones::Alarm,
+                grid.get_grid_pos(transform.translation.truncate()),
+                amount * ANT_ALARM_DEPOSIT,
+                &settings,
+            );
+        }
+        let deposit = castes.profile(*caste).deposit * genome.deposit_rate;
+        if deposit <= 0.0 {
+            continue;
+        }
+        pheromone_map.deposit(
+            *colony_id,
+            ant.trail_pheromone(),
+            grid.get_grid_pos(transform.translation.truncate()),
+            amount * deposit,
+            &settings,
+        );
+    }
+}
+
+/// Ants of aggressive colonies bite the rivals they touch
+pub(crate) fn ant_fight(
+    mut commands: Commands,
+    mut ants: Query<(
+        Entity,
+        &Transform,
+        &mut Ant,
+        &ColonyId,
+        &Caste,
+        &AntPerception,
+    )>,
+    colonies: Res<Colonies>,
+    castes: Res<CasteSettings>,
+    grid: Res<Grid>,
+    time: Res<Time>,
+) {
+    let mut bites = Vec::new();
+    for (_, transform, _, colony_id, caste, perception) in ants.iter() {
+        if !colonies.get(*colony_id).aggressive {
+            continue;
+        }
+        let damage = ANT_FIGHT_DAMAGE * castes.profile(*caste).attack * time.delta_secs();
+        let ant_position = transform.translation.truncate();
+        for &other in &perception.visible_ants {
+            if let Ok((_, other_transform, _, other_colony, _, _)) = ants.get(other) {
+                let other_position = other_transform.translation.truncate();
+                if other_colony != colony_id
+                    && grid.distance(ant_position, other_position) <= ANT_CONTACT_DISTANCE
+                {
+                    bites.push((other, damage));
+                }
+            }
+        }
+    }
+
+    for (bitten, damage) in bites {
+        if let Ok((entity, _, mut ant, _, _, _)) = ants.get_mut(bitten) {
+            if ant.energy <= 0.0 {
+                continue; // Already dead this frame
+            }
+            ant.energy -= damage;
+            if ant.energy <= 0.0 {
+                commands.entity(entity).try_despawn();
+            }
+        }
+    }
+}
+
 fn ant_focused(
-    ants: Query<(&Transform, &Ant, Entity), With<Ant>>,
+    ants: Query<(&Transform, &Ant, Entity, &Caste, &Genome, &AntPerception), With<Ant>>,
     focused_entity: Res<FocusedEntity>,
     mut gizmos: Gizmos,
     grid: Res<Grid>,
     ants_settings: Res<AntSettings>,
+    castes: Res<CasteSettings>,
+    ui_controls: Res<UiControls>,
 ) {
+    if !ui_controls.show_ant_views {
+        return;
+    }
     if let Some(focused_entity) = focused_entity.0 {
-        if let Ok((transform, ant, ant_entity)) = ants.get(focused_entity) {
+        if let Ok((transform, ant, ant_entity, caste, genome, perception)) =
+            ants.get(focused_entity)
+        {
+            let ants_settings = ants_settings
+                .with_caste(castes.profile(*caste))
+                .with_genome(genome);
             ant.debug_view(transform, &mut gizmos, &ants_settings);
 
             let ant_position = transform.translation.truncate();
             let cells_in_area =
                 grid.get_cells_in_area_from_world(ant_position, ants_settings.view_distance);
-            let view_cone = ant.get_view_cone(transform, &ants_settings);
 
             // (pos ,(ants, pheromones, food))
             cells_in_area
@@ -232,9 +971,14 @@ fn ant_focused(
                     );
                     ants_grid_entities.into_iter().for_each(|(_, entitie)| {
                         if entitie != ant_entity {
-                            if let Ok((other_ant_transform, _, _)) = ants.get(entitie) {
-                                let other_ant_position = other_ant_transform.translation.truncate();
-                                if view_cone.contains(other_ant_position, ANT_SIZE / 2.) {
+                            if let Ok((other_ant_transform, ..)) = ants.get(entitie) {
+                                // Draw towards the closest image of the other ant on a torus
+                                let other_ant_position = ant_position
+                                    + grid.delta(
+                                        ant_position,
+                                        other_ant_transform.translation.truncate(),
+                                    );
+                                if perception.visible_ants.contains(&entitie) {
                                     // Ant sees another ant
                                     // draw a redline beetween the two ants
                                     gizmos.line_2d(
@@ -258,161 +1002,218 @@ fn ant_focused(
     }
 }
 fn move_ants(
-    mut ants: Query<(&mut Transform, &mut Ant), With<Ant>>,
+    mut ants: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Ant,
+            &AntPerception,
+            &Caste,
+            &Genome,
+            &ColonyId,
+        ),
+        With<Ant>,
+    >,
     ants_settings: Res<AntSettings>,
+    castes: Res<CasteSettings>,
+    colonies: Res<Colonies>,
     time: Res<Time>,
     grid: Res<Grid>,
+    mut rng: ResMut<SimulationRng>,
 ) {
     let (min, max) = grid.get_boundaries();
+    let bounded = grid.topology() == Topology::Bounded;
     let border_threshold = ants_settings.view_distance * 1.5;
     let delta_secs = time.delta_secs();
+    let rng_key = rng.gen();
 
     ants.par_iter_mut()
         .batching_strategy(BatchingStrategy {
             batch_size_limits: 1..500,
             ..Default::default()
         })
-        .for_each(|(mut ant_transform, mut ant)| {
-            let ant_position = ant_transform.translation.truncate();
+        .for_each(
+            |(entity, mut ant_transform, mut ant, perception, caste, genome, colony_id)| {
+                let mut rng = SimulationRng::for_entity(rng_key, entity);
+                let profile = castes.profile(*caste);
+                let caste_settings = ants_settings.with_caste(profile);
+                let ant_position = ant_transform.translation.truncate();
+                let jitter = genome.turn_noise * profile.exploration;
 
-            // If the ant has no specific target, it will randomly steer
-            if let DesiredTarget::NOTHING = ant.desired_target {
-                // Steer away from borders if close enough
-                if ant_position.x < min.x + border_threshold {
-                    ant.desired_direction.x = ant.desired_direction.x + 1.0;
-                } else if ant_position.x > max.x - border_threshold {
-                    ant.desired_direction.x = ant.desired_direction.x - 1.0;
+                // Ants sent home walk straight there
+                if let DesiredTarget::NEST = ant.desired_target {
+                    let to_nest = grid.delta(ant_position, colonies.get(*colony_id).nest_position);
+                    if to_nest.length() <= ants_settings.nest_size {
+                        ant.desired_target = DesiredTarget::NOTHING;
+                    } else {
+                        ant.desired_direction = to_nest.normalize_or_zero();
+                    }
                 }
-                ant.desired_direction.y =
-                    ant.desired_direction.y + (rand::random::<f32>() - 0.5) * 0.4;
-                if ant_position.y < min.y + border_threshold {
-                    ant.desired_direction.y = ant.desired_direction.y + 1.0;
-                } else if ant_position.y > max.y - border_threshold {
-                    ant.desired_direction.y = ant.desired_direction.y - 1.0;
+
+                // If the ant has no specific target, it will randomly steer
+                if let DesiredTarget::NOTHING = ant.desired_target {
+                    // Guards wander back once too far from their nest
+                    if let Some(guard_radius) = profile.guard_radius {
+                        let to_nest =
+                            grid.delta(ant_position, colonies.get(*colony_id).nest_position);
+                        if to_nest.length() > guard_radius {
+                            ant.desired_direction += to_nest.normalize();
+                        }
+                    }
+                    // Steer away from borders if close enough, a torus has none
+                    if bounded && ant_position.x < min.x + border_threshold {
+                        ant.desired_direction.x += 1.0;
+                    } else if bounded && ant_position.x > max.x - border_threshold {
+                        ant.desired_direction.x -= 1.0;
+                    }
+                    ant.desired_direction.y += (rng.gen::<f32>() - 0.5) * jitter;
+                    if bounded && ant_position.y < min.y + border_threshold {
+                        ant.desired_direction.y += 1.0;
+                    } else if bounded && ant_position.y > max.y - border_threshold {
+                        ant.desired_direction.y -= 1.0;
+                    }
+                    ant.desired_direction.x += (rng.gen::<f32>() - 0.5) * jitter;
+                    ant.desired_direction = ant.desired_direction.normalize_or_zero();
                 }
-                ant.desired_direction.x =
-                    ant.desired_direction.x + (rand::random::<f32>() - 0.5) * 0.4;
-                ant.desired_direction = ant.desired_direction.normalize_or_zero();
-            }
 
-            // Current forward direction of the ant
-            let current_direction = ant_transform.rotation * Vec3::Y;
-
-            // Calculate the angle between the current direction and the desired direction
-            let angle = current_direction.angle_between(ant.desired_direction.extend(0.0));
-
-            // Calculate the rotation step based on the ant's rotation speed and the elapsed time
-            let rotation_speed = match ant.desired_target {
-                DesiredTarget::FOOD => ANT_ROTATION_SPEED * 3.0,
-                // case searching for pheromone
-                _ => match (
-                    ant_position.x < min.x + ants_settings.view_distance,
-                    ant_position.x > max.x - ants_settings.view_distance,
-                    ant_position.y < min.y + ants_settings.view_distance,
-                    ant_position.y > max.y - ants_settings.view_distance,
-                ) {
-                    (true, _, _, _) | (_, true, _, _) | (_, _, true, _) | (_, _, _, true) => {
-                        // Increase turning speed when steering from borders
-                        ANT_ROTATION_SPEED * 5.0
+                // Keep some distance from the ants in front without changing the goal
+                let steering_direction = (ant.desired_direction
+                    + perception.separation * ANT_SEPARATION_WEIGHT)
+                    .normalize_or_zero();
+
+                // Current forward direction of the ant
+                let current_direction = ant_transform.rotation * Vec3::Y;
+
+                // Calculate the angle between the current direction and the desired direction
+                let angle = current_direction.angle_between(steering_direction.extend(0.0));
+
+                // Calculate the rotation step based on the ant's rotation speed and the elapsed time
+                let rotation_speed = match ant.desired_target {
+                    DesiredTarget::FOOD | DesiredTarget::NEST | DesiredTarget::DANGER => {
+                        ANT_ROTATION_SPEED * 3.0
                     }
-                    _ => ANT_ROTATION_SPEED,
-                },
-            };
-            let rotation_step = rotation_speed * delta_secs;
-
-            // Determine the new rotation
-            let new_rotation = if angle < rotation_step {
-                Quat::from_rotation_arc(current_direction, ant.desired_direction.extend(0.0))
-            } else {
-                Quat::from_rotation_arc(
-                    current_direction,
-                    current_direction
-                        .lerp(ant.desired_direction.extend(0.0), rotation_step / angle),
-                )
-            };
+                    // case searching for pheromone
+                    _ if !bounded => ANT_ROTATION_SPEED,
+                    _ => match (
+                        ant_position.x < min.x + ants_settings.view_distance,
+                        ant_position.x > max.x - ants_settings.view_distance,
+                        ant_position.y < min.y + ants_settings.view_distance,
+                        ant_position.y > max.y - ants_settings.view_distance,
+                    ) {
+                        (true, _, _, _) | (_, true, _, _) | (_, _, true, _) | (_, _, _, true) => {
+                            // Increase turning speed when steering from borders
+                            ANT_ROTATION_SPEED * 5.0
+                        }
+                        _ => ANT_ROTATION_SPEED,
+                    },
+                };
+                let rotation_step = rotation_speed * delta_secs;
 
-            // Apply the new rotation to the ant
-            ant_transform.rotation = new_rotation * ant_transform.rotation;
+                // Determine the new rotation
+                let new_rotation = if angle < rotation_step {
+                    Quat::from_rotation_arc(current_direction, steering_direction.extend(0.0))
+                } else {
+                    Quat::from_rotation_arc(
+                        current_direction,
+                        current_direction
+                            .lerp(steering_direction.extend(0.0), rotation_step / angle),
+                    )
+                };
 
-            // Move the ant forward in the direction it is facing
-            let forward_movement =
-                ant_transform.rotation * Vec3::Y * ants_settings.speed * delta_secs;
-            ant_transform.translation += forward_movement;
+                // Apply the new rotation to the ant
+                ant_transform.rotation = new_rotation * ant_transform.rotation;
 
-            // Constrain the ant to the grid space
-            ant_transform.translation.x = ant_transform.translation.x.clamp(min.x, max.x);
-            ant_transform.translation.y = ant_transform.translation.y.clamp(min.y, max.y);
-        });
+                // Move the ant forward in the direction it is facing
+                let forward_movement = ant_transform.rotation
+                    * Vec3::Y
+                    * caste_settings.speed
+                    * grid.terrain_at(ant_position).speed()
+                    * delta_secs;
+                // Walls only stop ants from entering, one caught inside can walk out
+                if grid.is_wall_at((ant_transform.translation + forward_movement).truncate())
+                    && !grid.is_wall_at(ant_position)
+                {
+                    // Turn around instead of walking into a wall
+                    ant_transform.rotation *= Quat::from_rotation_z(std::f32::consts::PI);
+                    ant.desired_direction = -ant.desired_direction;
+                } else {
+                    ant_transform.translation += forward_movement;
+                }
+
+                // Constrain the ant to the grid space, wrapping around on a torus
+                let constrained = grid.constrain(ant_transform.translation.truncate());
+                ant_transform.translation.x = constrained.x;
+                ant_transform.translation.y = constrained.y;
+            },
+        );
 }
 
 fn check_mouse(
-    mut ants: Query<(&Transform, &mut Ant), With<Ant>>,
+    mut ants: Query<(&Transform, &mut Ant, &Caste, &Genome), With<Ant>>,
     windows: Query<&Window, With<PrimaryWindow>>,
-    buttons: Res<ButtonInput<MouseButton>>,
+    actions: Actions,
     ants_settings: Res<AntSettings>,
+    castes: Res<CasteSettings>,
     mut gizmos: Gizmos,
-    _grid: Res<Grid>,
-    camera_transform: Query<&Transform, With<Camera>>,
+    grid: Res<Grid>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
     focused_entity: Res<FocusedEntity>,
+    ui_controls: Res<UiControls>,
+    pointer_over_ui: Res<PointerOverUi>,
+    active_tool: Res<ActiveTool>,
 ) {
     let window = windows.single();
-    let camera_transform = camera_transform.single();
-    if buttons.pressed(MouseButton::Left) {
+    let (camera, camera_transform) = camera_query.single();
+    if actions.pressed(Action::Lure) && !pointer_over_ui.0 && active_tool.tool == Tool::Select {
         if let Some(focused_entity) = focused_entity.0 {
-            if let Ok((ant_transform, mut ant)) = ants.get_mut(focused_entity) {
+            if let Ok((ant_transform, mut ant, caste, genome)) = ants.get_mut(focused_entity) {
+                let ants_settings = ants_settings
+                    .with_caste(castes.profile(*caste))
+                    .with_genome(genome);
                 let ant_position = ant_transform.translation.truncate();
-                let view_cone = ViewCone::new(
-                    ant_position,
-                    ants_settings.view_distance,
-                    ants_settings.view_angle,
-                    ant_transform.rotation.to_euler(EulerRot::XYZ).2,
-                );
-
-                if let Some(cursor_position) = window.cursor_position() {
-                    let cursor_world_position =
-                        window_to_world(cursor_position, window, camera_transform);
+                let view_cone = ant.get_view_cone(ant_transform, &ants_settings, &grid);
 
+                if let Some(cursor_world_position) =
+                    cursor_to_world(window, camera, camera_transform)
+                {
                     // Draw a blue dot at the mouse click position
-                    gizmos.circle_2d(
-                        cursor_world_position,
-                        5.0,
-                        LinearRgba::new(0.0, 0.0, 1.0, 1.0),
-                    );
+                    if ui_controls.show_ant_views {
+                        gizmos.circle_2d(
+                            cursor_world_position,
+                            5.0,
+                            LinearRgba::new(0.0, 0.0, 1.0, 1.0),
+                        );
+                    }
 
                     if view_cone.contains(cursor_world_position, 0.0) {
                         ant.desired_target = DesiredTarget::FOOD;
-                        ant.desired_direction = (cursor_world_position - ant_position).normalize();
+                        ant.known_food = Some(cursor_world_position);
+                        ant.desired_direction =
+                            grid.delta(ant_position, cursor_world_position).normalize();
                         println!(
                                 "Ant at position {:?} sees the mouse click as food and sets direction towards it",
                                 ant_position
                             );
 
                         // Draw a green dot at the ant's desired direction
-                        let desired_position =
-                            ant_position + ant.desired_direction * ants_settings.view_distance;
-                        gizmos.circle_2d(
-                            desired_position,
-                            5.0,
-                            LinearRgba::from_f32_array(DEBUG_ANT_VIEW_COLOR_ALERT),
-                        );
+                        if ui_controls.show_ant_views {
+                            let desired_position =
+                                ant_position + ant.desired_direction * ants_settings.view_distance;
+                            gizmos.circle_2d(
+                                desired_position,
+                                5.0,
+                                LinearRgba::from_f32_array(DEBUG_ANT_VIEW_COLOR_ALERT),
+                            );
+                        }
                     }
                 }
             }
         }
-    } else {
+    } else if actions.just_released(Action::Lure) {
         if let Some(focused_entity) = focused_entity.0 {
-            if let Ok((_, mut ant)) = ants.get_mut(focused_entity) {
+            if let Ok((_, mut ant, _, _)) = ants.get_mut(focused_entity) {
                 ant.desired_target = DesiredTarget::NOTHING;
             }
         }
     }
 }
-
-fn draw_nest(mut gizmos: Gizmos, ant_settings: Res<AntSettings>) {
-    // Draw the nest
-    gizmos.circle_2d(
-        ant_settings.nest_position,
-        ant_settings.nest_size,
-        LinearRgba::from_f32_array(NEST_COLOR),
-    );
-}
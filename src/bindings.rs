@@ -0,0 +1,433 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::BINDINGS_FILE;
+
+/// Maps input actions to keys and mouse buttons. The defaults can be overridden from
+/// `bindings.cfg` in the working directory, one `action = binding, binding` per line:
+///
+/// ```text
+/// # Pan with the right mouse button and lure with the middle one
+/// drag_pan = MouseRight
+/// lure = MouseMiddle
+/// ```
+///
+/// Keys use their `KeyCode` name and mouse buttons are `MouseLeft`, `MouseMiddle`, `MouseRight`
+pub struct BindingsPlugin;
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputBindings::from_file(BINDINGS_FILE));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    /// Hold and move the mouse to pan
+    DragPan,
+    ZoomIn,
+    ZoomOut,
+    FitWorld,
+    CycleFollow,
+    /// Focus an ant, drag a box around several
+    Select,
+    /// Point the focused ant toward the cursor
+    Lure,
+    /// Hold to add a box selection to the current one
+    ExtendSelection,
+    ClearSelection,
+    KillSelection,
+    /// Use the active tool
+    Paint,
+    /// Undo the painting tools
+    Erase,
+    ToolSelect,
+    ToolFood,
+    ToolWall,
+    ToolPheromone,
+    ToolErasePheromone,
+    ToolNest,
+    BrushSmaller,
+    BrushLarger,
+    CyclePheromone,
+    CycleColony,
+    ToggleGrid,
+    ToggleAntViews,
+    TogglePheromones,
+    ToggleNests,
+    ToggleCharts,
+    TogglePredators,
+    Screenshot,
+    ToggleRecording,
+    /// Hold while pressing a bookmark to save the view instead of recalling it
+    SaveBookmark,
+    Bookmark1,
+    Bookmark2,
+    Bookmark3,
+    Bookmark4,
+    Bookmark5,
+    Bookmark6,
+    Bookmark7,
+    Bookmark8,
+}
+
+impl Action {
+    pub const ALL: [Action; 43] = [
+        Action::PanUp,
+        Action::PanDown,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::DragPan,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::FitWorld,
+        Action::CycleFollow,
+        Action::Select,
+        Action::Lure,
+        Action::ExtendSelection,
+        Action::ClearSelection,
+        Action::KillSelection,
+        Action::Paint,
+        Action::Erase,
+        Action::ToolSelect,
+        Action::ToolFood,
+        Action::ToolWall,
+        Action::ToolPheromone,
+        Action::ToolErasePheromone,
+        Action::ToolNest,
+        Action::BrushSmaller,
+        Action::BrushLarger,
+        Action::CyclePheromone,
+        Action::CycleColony,
+        Action::ToggleGrid,
+        Action::ToggleAntViews,
+        Action::TogglePheromones,
+        Action::ToggleNests,
+        Action::ToggleCharts,
+        Action::TogglePredators,
+        Action::Screenshot,
+        Action::ToggleRecording,
+        Action::SaveBookmark,
+        Action::Bookmark1,
+        Action::Bookmark2,
+        Action::Bookmark3,
+        Action::Bookmark4,
+        Action::Bookmark5,
+        Action::Bookmark6,
+        Action::Bookmark7,
+        Action::Bookmark8,
+    ];
+
+    /// Name in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::PanUp => "pan_up",
+            Action::PanDown => "pan_down",
+            Action::PanLeft => "pan_left",
+            Action::PanRight => "pan_right",
+            Action::DragPan => "drag_pan",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::FitWorld => "fit_world",
+            Action::CycleFollow => "cycle_follow",
+            Action::Select => "select",
+            Action::Lure => "lure",
+            Action::ExtendSelection => "extend_selection",
+            Action::ClearSelection => "clear_selection",
+            Action::KillSelection => "kill_selection",
+            Action::Paint => "paint",
+            Action::Erase => "erase",
+            Action::ToolSelect => "tool_select",
+            Action::ToolFood => "tool_food",
+            Action::ToolWall => "tool_wall",
+            Action::ToolPheromone => "tool_pheromone",
+            Action::ToolErasePheromone => "tool_erase_pheromone",
+            Action::ToolNest => "tool_nest",
+            Action::BrushSmaller => "brush_smaller",
+            Action::BrushLarger => "brush_larger",
+            Action::CyclePheromone => "cycle_pheromone",
+            Action::CycleColony => "cycle_colony",
+            Action::ToggleGrid => "toggle_grid",
+            Action::ToggleAntViews => "toggle_ant_views",
+            Action::TogglePheromones => "toggle_pheromones",
+            Action::ToggleNests => "toggle_nests",
+            Action::ToggleCharts => "toggle_charts",
+            Action::TogglePredators => "toggle_predators",
+            Action::Screenshot => "screenshot",
+            Action::ToggleRecording => "toggle_recording",
+            Action::SaveBookmark => "save_bookmark",
+            Action::Bookmark1 => "bookmark_1",
+            Action::Bookmark2 => "bookmark_2",
+            Action::Bookmark3 => "bookmark_3",
+            Action::Bookmark4 => "bookmark_4",
+            Action::Bookmark5 => "bookmark_5",
+            Action::Bookmark6 => "bookmark_6",
+            Action::Bookmark7 => "bookmark_7",
+            Action::Bookmark8 => "bookmark_8",
+        }
+    }
+
+    fn default_bindings(&self) -> Vec<Binding> {
+        use Binding::{Key, Mouse};
+        match self {
+            Action::PanUp => vec![Key(KeyCode::KeyW), Key(KeyCode::ArrowUp)],
+            Action::PanDown => vec![Key(KeyCode::KeyS), Key(KeyCode::ArrowDown)],
+            Action::PanLeft => vec![Key(KeyCode::KeyA), Key(KeyCode::ArrowLeft)],
+            Action::PanRight => vec![Key(KeyCode::KeyD), Key(KeyCode::ArrowRight)],
+            Action::DragPan => vec![Mouse(MouseButton::Middle)],
+            Action::ZoomIn => vec![Key(KeyCode::Equal), Key(KeyCode::NumpadAdd)],
+            Action::ZoomOut => vec![Key(KeyCode::Minus), Key(KeyCode::NumpadSubtract)],
+            Action::FitWorld => vec![Key(KeyCode::KeyF), Key(KeyCode::Home)],
+            Action::CycleFollow => vec![Key(KeyCode::KeyM)],
+            Action::Select => vec![Mouse(MouseButton::Left)],
+            Action::Lure => vec![Mouse(MouseButton::Right)],
+            Action::ExtendSelection => vec![Key(KeyCode::ShiftLeft), Key(KeyCode::ShiftRight)],
+            Action::ClearSelection => vec![Key(KeyCode::Escape)],
+            Action::KillSelection => vec![Key(KeyCode::Delete)],
+            Action::Paint => vec![Mouse(MouseButton::Left)],
+            Action::Erase => vec![Mouse(MouseButton::Right)],
+            Action::ToolSelect => vec![Key(KeyCode::Digit1)],
+            Action::ToolFood => vec![Key(KeyCode::Digit2)],
+            Action::ToolWall => vec![Key(KeyCode::Digit3)],
+            Action::ToolPheromone => vec![Key(KeyCode::Digit4)],
+            Action::ToolErasePheromone => vec![Key(KeyCode::Digit5)],
+            Action::ToolNest => vec![Key(KeyCode::Digit6)],
+            Action::BrushSmaller => vec![Key(KeyCode::BracketLeft)],
+            Action::BrushLarger => vec![Key(KeyCode::BracketRight)],
+            Action::CyclePheromone => vec![Key(KeyCode::KeyT)],
+            Action::CycleColony => vec![Key(KeyCode::KeyY)],
+            Action::ToggleGrid => vec![Key(KeyCode::KeyG)],
+            Action::ToggleAntViews => vec![Key(KeyCode::KeyV)],
+            Action::TogglePheromones => vec![Key(KeyCode::KeyP)],
+            Action::ToggleNests => vec![Key(KeyCode::KeyN)],
+            Action::ToggleCharts => vec![Key(KeyCode::KeyC)],
+            Action::TogglePredators => vec![Key(KeyCode::KeyR)],
+            Action::Screenshot => vec![Key(KeyCode::F12)],
+            Action::ToggleRecording => vec![Key(KeyCode::F11)],
+            Action::SaveBookmark => vec![Key(KeyCode::ControlLeft), Key(KeyCode::ControlRight)],
+            Action::Bookmark1 => vec![Key(KeyCode::F1)],
+            Action::Bookmark2 => vec![Key(KeyCode::F2)],
+            Action::Bookmark3 => vec![Key(KeyCode::F3)],
+            Action::Bookmark4 => vec![Key(KeyCode::F4)],
+            Action::Bookmark5 => vec![Key(KeyCode::F5)],
+            Action::Bookmark6 => vec![Key(KeyCode::F6)],
+            Action::Bookmark7 => vec![Key(KeyCode::F7)],
+            Action::Bookmark8 => vec![Key(KeyCode::F8)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+/// Keys that can be named in the config file
+const KEYS: [KeyCode; 77] = [
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Space,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Escape,
+    KeyCode::Backspace,
+    KeyCode::Delete,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Semicolon,
+    KeyCode::Quote,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::NumpadAdd,
+    KeyCode::NumpadSubtract,
+];
+
+impl Binding {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "MouseLeft" => Some(Binding::Mouse(MouseButton::Left)),
+            "MouseMiddle" => Some(Binding::Mouse(MouseButton::Middle)),
+            "MouseRight" => Some(Binding::Mouse(MouseButton::Right)),
+            _ => KEYS
+                .into_iter()
+                .find(|key| format!("{key:?}") == name)
+                .map(Binding::Key),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct InputBindings(pub HashMap<Action, Vec<Binding>>);
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self(
+            Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_bindings()))
+                .collect(),
+        )
+    }
+}
+
+impl InputBindings {
+    /// The defaults, overridden by the file when it exists
+    pub fn from_file(path: impl AsRef<Path>) -> Self {
+        let mut bindings = Self::default();
+        if let Ok(config) = fs::read_to_string(path.as_ref()) {
+            for error in bindings.apply_config(&config) {
+                warn!("{:?}: {error}", path.as_ref());
+            }
+        }
+        bindings
+    }
+
+    /// Replace the bindings of every action in the config, returning the lines that
+    /// couldn't be read
+    pub fn apply_config(&mut self, config: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (number, line) in config.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((name, bindings)) = line.split_once('=') else {
+                errors.push(format!("line {}: expected `action = bindings`", number + 1));
+                continue;
+            };
+            let Some(action) = Action::ALL
+                .into_iter()
+                .find(|action| action.name() == name.trim())
+            else {
+                errors.push(format!(
+                    "line {}: unknown action {}",
+                    number + 1,
+                    name.trim()
+                ));
+                continue;
+            };
+            let mut parsed = Vec::new();
+            for binding in bindings.split(',').map(str::trim) {
+                match Binding::parse(binding) {
+                    Some(binding) => parsed.push(binding),
+                    None if binding.is_empty() => {}
+                    None => errors.push(format!("line {}: unknown input {binding}", number + 1)),
+                }
+            }
+            self.0.insert(action, parsed);
+        }
+        errors
+    }
+
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0.get(&action).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// Current state of the bound actions
+#[derive(SystemParam)]
+pub struct Actions<'w> {
+    bindings: Res<'w, InputBindings>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+}
+
+impl Actions<'_> {
+    fn any(
+        &self,
+        action: Action,
+        key: fn(&ButtonInput<KeyCode>, KeyCode) -> bool,
+        mouse: fn(&ButtonInput<MouseButton>, MouseButton) -> bool,
+    ) -> bool {
+        self.bindings
+            .get(action)
+            .iter()
+            .any(|binding| match *binding {
+                Binding::Key(code) => key(&self.keys, code),
+                Binding::Mouse(button) => mouse(&self.mouse, button),
+            })
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.any(action, ButtonInput::pressed, ButtonInput::pressed)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.any(action, ButtonInput::just_pressed, ButtonInput::just_pressed)
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.any(
+            action,
+            ButtonInput::just_released,
+            ButtonInput::just_released,
+        )
+    }
+}
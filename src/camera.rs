@@ -1,6 +1,22 @@
-use bevy::{input::mouse::MouseWheel, prelude::*, window::PrimaryWindow};
-
-use crate::{utils::window_to_world, CAMERA_FOCUS_RANGE, CAMERA_MAX_ZOOM, CAMERA_MIN_ZOOM};
+use bevy::{
+    color::palettes::css::WHITE, input::mouse::MouseWheel, prelude::*, window::PrimaryWindow,
+};
+
+use crate::{
+    ant::{Ant, AntSettings, DesiredTarget},
+    bindings::{Action, Actions},
+    colony::{Colonies, ColonyId},
+    food::FoodSource,
+    grid::{Grid, GridEntityType},
+    predator::Predator,
+    selection::Selected,
+    tools::{ActiveTool, Tool},
+    ui::PointerOverUi,
+    utils::{cursor_to_world, window_to_world},
+    ANT_SIZE, CAMERA_BOUNDS_MARGIN, CAMERA_CYCLE_INTERVAL, CAMERA_DIRECTOR_PADDING,
+    CAMERA_DIRECTOR_SMOOTHING, CAMERA_MIN_SCALE, CAMERA_PAN_SPEED, CAMERA_PICK_RADIUS,
+    CAMERA_SMOOTHING, PREDATOR_SIZE,
+};
 
 pub struct CameraPlugin;
 
@@ -21,44 +37,221 @@ pub struct FocusedEntity(pub Option<Entity>);
 #[derive(Default, Resource)]
 pub struct CameraTarget(pub Option<Vec3>);
 
+/// Something in the world the cursor can point at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pick {
+    Entity(Entity),
+    Nest(ColonyId),
+}
+
+/// The closest pickable thing under the cursor, with its position and radius
+#[derive(Resource, Debug, Default)]
+pub struct Hovered(pub Option<(Pick, Vec2, f32)>);
+
+/// What the camera keeps in view besides a focused entity, cycled with `Action::CycleFollow`
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CameraFollow {
+    #[default]
+    Free,
+    /// Centroid of the selected ants
+    Selection,
+    Nest(ColonyId),
+    /// Focus the next active forager every few seconds
+    Foragers,
+    /// Slowly frame all the active foragers, for recordings
+    Director,
+}
+
+impl CameraFollow {
+    fn next(self, colonies: usize) -> Self {
+        match self {
+            CameraFollow::Free => CameraFollow::Selection,
+            CameraFollow::Selection if colonies > 0 => CameraFollow::Nest(ColonyId(0)),
+            CameraFollow::Nest(ColonyId(id)) if id + 1 < colonies => {
+                CameraFollow::Nest(ColonyId(id + 1))
+            }
+            CameraFollow::Selection | CameraFollow::Nest(_) => CameraFollow::Foragers,
+            CameraFollow::Foragers => CameraFollow::Director,
+            CameraFollow::Director => CameraFollow::Free,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            CameraFollow::Free => "Free".to_string(),
+            CameraFollow::Selection => "Selection".to_string(),
+            CameraFollow::Nest(colony) => format!("Nest {}", colony.0 + 1),
+            CameraFollow::Foragers => "Foragers".to_string(),
+            CameraFollow::Director => "Director".to_string(),
+        }
+    }
+}
+
+/// Saved camera position and zoom, F1 to F8 recall them and Ctrl + F1 to F8 save them
+#[derive(Resource, Debug, Default)]
+pub struct CameraBookmarks(pub [Option<Vec3>; 8]);
+
+const BOOKMARK_ACTIONS: [Action; 8] = [
+    Action::Bookmark1,
+    Action::Bookmark2,
+    Action::Bookmark3,
+    Action::Bookmark4,
+    Action::Bookmark5,
+    Action::Bookmark6,
+    Action::Bookmark7,
+    Action::Bookmark8,
+];
+
+/// Zoom and panning limits of the camera, derived from the size of the world.
+/// The zoom is the camera scale, a smaller scale shows a smaller area closer
+#[derive(Resource, Debug, Clone)]
+pub struct CameraSettings {
+    /// Scale of the closest zoom
+    pub min_scale: f32,
+    /// Scale of the farthest zoom, the whole world fits in the window
+    pub max_scale: f32,
+    /// Distance the view can go past the edges of the world
+    pub margin: f32,
+    /// Area covered by the world
+    pub world: Rect,
+    /// Rate the camera catches up with its target
+    pub smoothing: f32,
+    /// Catch up rate in director mode, slower for steady recordings
+    pub director_smoothing: f32,
+}
+
+impl CameraSettings {
+    pub fn new(world: Rect, window_size: Vec2) -> Self {
+        let mut settings = Self {
+            min_scale: CAMERA_MIN_SCALE,
+            max_scale: CAMERA_MIN_SCALE,
+            margin: CAMERA_BOUNDS_MARGIN,
+            world,
+            smoothing: CAMERA_SMOOTHING,
+            director_smoothing: CAMERA_DIRECTOR_SMOOTHING,
+        };
+        settings.max_scale = settings.fit_scale(window_size).max(CAMERA_MIN_SCALE);
+        settings
+    }
+
+    /// Scale framing the whole world and its margin in a view of `view_size`
+    pub fn fit_scale(&self, view_size: Vec2) -> f32 {
+        ((self.world.size() + 2.0 * self.margin) / view_size).max_element()
+    }
+
+    pub fn clamp_scale(&self, scale: f32) -> f32 {
+        scale.clamp(self.min_scale, self.max_scale)
+    }
+
+    /// Closest camera position whose view, `half_view` on each side, stays inside the
+    /// world and its margin. The view is centered on an axis the world doesn't fill
+    pub fn clamp_position(&self, position: Vec2, half_view: Vec2) -> Vec2 {
+        let min = self.world.min - self.margin + half_view;
+        let max = self.world.max + self.margin - half_view;
+        let center = self.world.center();
+        let clamp_axis = |value: f32, min: f32, max: f32, center: f32| {
+            if min > max {
+                center
+            } else {
+                value.clamp(min, max)
+            }
+        };
+        Vec2::new(
+            clamp_axis(position.x, min.x, max.x, center.x),
+            clamp_axis(position.y, min.y, max.y, center.y),
+        )
+    }
+}
+
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(FocusedEntity::default())
             .insert_resource(CameraTarget::default())
+            .init_resource::<CameraFollow>()
+            .init_resource::<CameraBookmarks>()
+            .init_resource::<Hovered>()
             .add_systems(Startup, setup)
             .add_systems(Update, pan_camera_system)
             .add_systems(Update, zoom_camera_system)
-            .add_systems(Update, focus_on_entity_system)
-            .add_systems(Update, move_camera_system);
+            .add_systems(
+                Update,
+                (
+                    fit_world_system,
+                    bookmark_system,
+                    follow_hotkeys,
+                    follow_system,
+                    hover_system,
+                    focus_on_entity_system,
+                    move_camera_system,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                clamp_camera_system
+                    .after(pan_camera_system)
+                    .after(zoom_camera_system)
+                    .after(move_camera_system),
+            )
+            .add_systems(Update, draw_hovered.after(hover_system));
     }
 }
 
-fn setup(mut commands: Commands) {
+fn setup(mut commands: Commands, grid: Res<Grid>, window: Single<&Window, With<PrimaryWindow>>) {
+    let (min, max) = grid.get_boundaries();
+    let settings = CameraSettings::new(Rect::from_corners(min, max), window.size());
+
+    // Start with the whole world in view
     commands.spawn((
-        Camera2d::default(),
+        Camera2d,
         Transform {
-            scale: Vec3::splat(CAMERA_MIN_ZOOM),
+            translation: settings.world.center().extend(0.0),
+            scale: Vec3::new(settings.max_scale, settings.max_scale, 1.0),
             ..Default::default()
         },
     ));
+    commands.insert_resource(settings);
 }
 
-/// System to pan the camera, taking scale into account
+/// System to pan the camera with the keyboard or by dragging, taking scale into account
 fn pan_camera_system(
     mut query: Query<&mut Transform, With<Camera2d>>,
-    mouse_input: Res<ButtonInput<MouseButton>>,
-    key_input: Res<ButtonInput<KeyCode>>,
+    actions: Actions,
     mut cursor_moved_events: EventReader<CursorMoved>,
     mut previous_cursor_position: Local<Option<Vec2>>,
     mut focused_entity: ResMut<FocusedEntity>,
     mut camera_target: ResMut<CameraTarget>,
+    mut follow: ResMut<CameraFollow>,
+    time: Res<Time<Real>>,
 ) {
     let mut camera_transform = query.single_mut();
 
-    // Only pan if Space is held and Left Mouse Button is pressed
-    if key_input.pressed(KeyCode::Space) && mouse_input.pressed(MouseButton::Left) {
+    // Keyboard panning keeps working while the simulation is paused
+    let direction = [
+        (Action::PanUp, Vec2::Y),
+        (Action::PanDown, Vec2::NEG_Y),
+        (Action::PanLeft, Vec2::NEG_X),
+        (Action::PanRight, Vec2::X),
+    ]
+    .into_iter()
+    .filter(|(action, _)| actions.pressed(*action))
+    .map(|(_, direction)| direction)
+    .sum::<Vec2>();
+    if direction != Vec2::ZERO {
+        focused_entity.0 = None;
+        camera_target.0 = None;
+        *follow = CameraFollow::Free;
+        let delta = direction.normalize()
+            * CAMERA_PAN_SPEED
+            * camera_transform.scale.truncate()
+            * time.delta_secs();
+        camera_transform.translation += delta.extend(0.0);
+    }
+
+    if actions.pressed(Action::DragPan) {
         focused_entity.0 = None; // Unfocus the entity when panning starts
         camera_target.0 = None; // Clear the camera target
+        *follow = CameraFollow::Free;
 
         if let Some(cursor_event) = cursor_moved_events.read().last() {
             let current_cursor_position = cursor_event.position;
@@ -81,54 +274,51 @@ fn pan_camera_system(
 
 /// System to zoom the camera in and out
 fn zoom_camera_system(
-    mut query: Query<&mut Transform, With<Camera2d>>,
-    key_input: Res<ButtonInput<KeyCode>>,
+    mut query: Query<(&Camera, &mut Transform), With<Camera2d>>,
+    actions: Actions,
     mut scroll_events: EventReader<MouseWheel>,
+    pointer_over_ui: Res<PointerOverUi>,
     windows: Query<&Window, With<PrimaryWindow>>,
+    settings: Res<CameraSettings>,
 ) {
     let window = windows.single();
-    let mut camera_transform = query.single_mut();
+    let (camera, mut camera_transform) = query.single_mut();
 
     // Handle keyboard zoom (centered on camera)
-    if key_input.pressed(KeyCode::Minus) {
-        camera_transform.scale *= Vec3::new(1.1, 1.1, 1.0); // Zoom out
-    } else if key_input.pressed(KeyCode::Equal) {
-        camera_transform.scale *= Vec3::new(0.9, 0.9, 1.0); // Zoom in
-    }
-
-    // Clamp zoom level using the constants
-    camera_transform.scale.x = camera_transform
-        .scale
-        .x
-        .clamp(CAMERA_MAX_ZOOM, CAMERA_MIN_ZOOM);
-    camera_transform.scale.y = camera_transform
-        .scale
-        .y
-        .clamp(CAMERA_MAX_ZOOM, CAMERA_MIN_ZOOM);
-
-    // Handle mouse scroll zoom (toward cursor)
-    if let Some(cursor_screen_pos) = window.cursor_position() {
+    let keyboard_factor = if actions.pressed(Action::ZoomOut) {
+        1.1 // Zoom out
+    } else if actions.pressed(Action::ZoomIn) {
+        0.9 // Zoom in
+    } else {
+        1.0
+    };
+    let scale = settings.clamp_scale(camera_transform.scale.x * keyboard_factor);
+    camera_transform.scale = Vec3::new(scale, scale, camera_transform.scale.z);
+
+    // Handle mouse scroll zoom (toward cursor), the wheel scrolls the UI under the cursor
+    if pointer_over_ui.0 {
+        scroll_events.clear();
+    } else if let Some(cursor_screen_pos) = window.cursor_position() {
         for scroll_event in scroll_events.read() {
             let zoom_factor = if scroll_event.y > 0.0 { 0.9 } else { 1.1 };
 
-            // Convert cursor position to world coordinates
-            let cursor_world_pos = window_to_world(cursor_screen_pos, window, &camera_transform);
+            // Convert cursor position to world coordinates, the camera has no parent so its
+            // global transform is its transform including the zoom applied so far
+            let Some(cursor_world_pos) = window_to_world(
+                cursor_screen_pos,
+                camera,
+                &GlobalTransform::from(*camera_transform),
+            ) else {
+                continue;
+            };
 
             // Compute the offset between the cursor and the camera
             let pre_zoom_offset = cursor_world_pos - camera_transform.translation.truncate();
 
-            // Apply zoom
-            camera_transform.scale *= Vec3::new(zoom_factor, zoom_factor, 1.0);
-
-            // Clamp zoom level using the constants
-            camera_transform.scale.x = camera_transform
-                .scale
-                .x
-                .clamp(CAMERA_MAX_ZOOM, CAMERA_MIN_ZOOM);
-            camera_transform.scale.y = camera_transform
-                .scale
-                .y
-                .clamp(CAMERA_MAX_ZOOM, CAMERA_MIN_ZOOM);
+            // Apply zoom within the limits, the factor is what was actually applied
+            let scale = settings.clamp_scale(camera_transform.scale.x * zoom_factor);
+            let zoom_factor = scale / camera_transform.scale.x;
+            camera_transform.scale = Vec3::new(scale, scale, camera_transform.scale.z);
 
             // Adjust the camera position to zoom toward the cursor
             let post_zoom_offset = pre_zoom_offset * zoom_factor;
@@ -141,36 +331,114 @@ fn zoom_camera_system(
     }
 }
 
+/// Find the ant, food source, predator or nest closest to the cursor. Only the grid cells
+/// around the cursor are searched, within a radius that stays the same size on screen at
+/// any zoom
+fn hover_system(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    pointer_over_ui: Res<PointerOverUi>,
+    active_tool: Res<ActiveTool>,
+    grid: Res<Grid>,
+    colonies: Res<Colonies>,
+    ant_settings: Res<AntSettings>,
+    ants: Query<&Transform, (With<Ant>, With<FocusableEntity>)>,
+    food_sources: Query<(&Transform, &FoodSource), With<FocusableEntity>>,
+    predators: Query<&Transform, (With<Predator>, With<FocusableEntity>)>,
+    mut hovered: ResMut<Hovered>,
+) {
+    hovered.0 = None;
+    if pointer_over_ui.0 || active_tool.tool != Tool::Select {
+        return;
+    }
+    let (camera, camera_transform) = *camera;
+    let Some(cursor) = cursor_to_world(&window, camera, camera_transform) else {
+        return;
+    };
+    let pick_radius = CAMERA_PICK_RADIUS * camera_transform.compute_transform().scale.x;
+    // Cells one further out catch entities whose bounds reach into the pick radius
+    let search_radius = pick_radius + grid.get_cell_size().max_element();
+
+    let ants = grid
+        .entities_near(GridEntityType::Ant, cursor, search_radius)
+        .filter_map(|entity| {
+            let transform = ants.get(entity).ok()?;
+            Some((
+                Pick::Entity(entity),
+                transform.translation.truncate(),
+                ANT_SIZE / 2.0 * transform.scale.x,
+            ))
+        });
+    let food = grid
+        .entities_near(GridEntityType::Food, cursor, search_radius)
+        .filter_map(|entity| {
+            let (transform, food) = food_sources.get(entity).ok()?;
+            Some((
+                Pick::Entity(entity),
+                transform.translation.truncate(),
+                food.radius(),
+            ))
+        });
+    let predators = grid
+        .entities_near(GridEntityType::Predator, cursor, search_radius)
+        .filter_map(|entity| {
+            let transform = predators.get(entity).ok()?;
+            Some((
+                Pick::Entity(entity),
+                transform.translation.truncate(),
+                PREDATOR_SIZE / 2.0,
+            ))
+        });
+    let nests = colonies.iter().map(|(colony_id, colony)| {
+        (
+            Pick::Nest(colony_id),
+            colony.nest_position,
+            ant_settings.nest_size,
+        )
+    });
+
+    // Distance to the edge so a small ant next to a big food source can still be picked
+    hovered.0 = ants
+        .chain(food)
+        .chain(predators)
+        .chain(nests)
+        .map(|pick| (pick, grid.distance(cursor, pick.1) - pick.2))
+        .filter(|(_, distance)| *distance <= pick_radius)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(pick, _)| pick);
+}
+
+fn draw_hovered(
+    mut gizmos: Gizmos,
+    hovered: Res<Hovered>,
+    camera: Single<&Transform, With<Camera2d>>,
+) {
+    if let Some((_, position, radius)) = hovered.0 {
+        gizmos.circle_2d(position, radius + 4.0 * camera.scale.x, WHITE);
+    }
+}
+
 /// System to focus and follow an entity with the camera
 fn focus_on_entity_system(
     entity_query: Query<(Entity, &Transform, &FocusableEntity), With<FocusableEntity>>,
-    mouse_input: Res<ButtonInput<MouseButton>>,
-    windows: Query<&Window, With<PrimaryWindow>>,
+    actions: Actions,
+    hovered: Res<Hovered>,
     mut focused_entity: ResMut<FocusedEntity>,
     mut camera_target: ResMut<CameraTarget>,
-    camera_query: Query<&Transform, With<Camera2d>>,
+    mut follow: ResMut<CameraFollow>,
 ) {
-    let window = windows.single();
-    let camera_transform = camera_query.single(); // Immutable access to the camera transform
-
-    // Handle mouse click to focus on an entity
-    if mouse_input.just_pressed(MouseButton::Left) {
-        if let Some(cursor_screen_pos) = window.cursor_position() {
-            let cursor_world_pos = window_to_world(cursor_screen_pos, window, camera_transform);
-
-            for (entity, entity_transform, focusable) in entity_query.iter() {
-                if cursor_world_pos.distance(entity_transform.translation.truncate())
-                    < CAMERA_FOCUS_RANGE
-                {
-                    focused_entity.0 = Some(entity);
-                    camera_target.0 = Some(Vec3::new(
-                        entity_transform.translation.x,
-                        entity_transform.translation.y,
-                        focusable.zoom,
-                    ));
-                    return;
-                }
+    // Handle mouse click to focus on what's under the cursor
+    if actions.just_pressed(Action::Select) {
+        match hovered.0 {
+            Some((Pick::Entity(entity), _, _)) => {
+                focused_entity.0 = Some(entity);
+                *follow = CameraFollow::Free;
+            }
+            Some((Pick::Nest(colony), _, _)) => {
+                focused_entity.0 = None;
+                *follow = CameraFollow::Nest(colony);
             }
+            None => {}
         }
     }
 
@@ -182,31 +450,46 @@ fn focus_on_entity_system(
                 entity_transform.translation.y,
                 focusable.zoom,
             ));
+        } else {
+            // The focused entity is gone
+            focused_entity.0 = None;
+            camera_target.0 = None;
         }
-    } else {
-        camera_target.0 = None;
     }
 }
 /// System to move the camera toward the target
 fn move_camera_system(
-    mut camera_query: Query<&mut Transform, With<Camera2d>>,
-    camera_target: Res<CameraTarget>,
+    mut camera_query: Query<(&mut Transform, &OrthographicProjection), With<Camera2d>>,
+    mut camera_target: ResMut<CameraTarget>,
+    focused_entity: Res<FocusedEntity>,
+    follow: Res<CameraFollow>,
+    settings: Res<CameraSettings>,
     time: Res<Time>,
 ) {
-    let mut camera_transform = camera_query.single_mut();
+    let (mut camera_transform, projection) = camera_query.single_mut();
 
     if let Some(target) = camera_target.0 {
-        // Separate position and zoom
-        let target_position = Vec3::new(target.x, target.y, camera_transform.translation.z);
-        let target_zoom = target.z;
+        // Separate position and zoom, aiming only where the camera is allowed to go
+        let target_zoom = settings.clamp_scale(target.z);
+        let target_position = settings
+            .clamp_position(target.truncate(), projection.area.half_size() * target_zoom)
+            .extend(camera_transform.translation.z);
+
+        let smoothing = if *follow == CameraFollow::Director {
+            settings.director_smoothing
+        } else {
+            settings.smoothing
+        };
+        // Never overshoot on a long frame
+        let step = (time.delta_secs() * smoothing).min(1.0);
 
         // Smoothly move the camera toward the target position
         let direction = target_position - camera_transform.translation;
-        camera_transform.translation += direction * time.delta_secs() * 5.0;
+        camera_transform.translation += direction * step;
 
         // Smoothly adjust the zoom level
         let zoom_diff = target_zoom - camera_transform.scale.x;
-        camera_transform.scale += Vec3::splat(zoom_diff * time.delta_secs() * 5.0);
+        camera_transform.scale += Vec3::splat(zoom_diff * step);
 
         // Snap to the target position if close enough
         if direction.length() < 0.1 {
@@ -218,14 +501,169 @@ fn move_camera_system(
             camera_transform.scale = Vec3::splat(target_zoom);
         }
 
-        // Clamp zoom level
-        camera_transform.scale.x = camera_transform
-            .scale
-            .x
-            .clamp(CAMERA_MAX_ZOOM, CAMERA_MIN_ZOOM);
-        camera_transform.scale.y = camera_transform
-            .scale
-            .y
-            .clamp(CAMERA_MAX_ZOOM, CAMERA_MIN_ZOOM);
+        // A target without an entity to follow is done once reached
+        if focused_entity.0.is_none() && direction.length() < 0.1 && zoom_diff.abs() < 0.01 {
+            camera_target.0 = None;
+        }
+    }
+}
+
+/// Frame the whole world
+fn fit_world_system(
+    actions: Actions,
+    settings: Res<CameraSettings>,
+    projection: Single<&OrthographicProjection, With<Camera2d>>,
+    mut focused_entity: ResMut<FocusedEntity>,
+    mut camera_target: ResMut<CameraTarget>,
+    mut follow: ResMut<CameraFollow>,
+) {
+    if actions.just_pressed(Action::FitWorld) {
+        focused_entity.0 = None;
+        *follow = CameraFollow::Free;
+        camera_target.0 = Some(
+            settings
+                .world
+                .center()
+                .extend(settings.fit_scale(projection.area.size())),
+        );
+    }
+}
+
+/// Recall a bookmark, or save the current view with Ctrl held
+fn bookmark_system(
+    actions: Actions,
+    camera: Single<&Transform, With<Camera2d>>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut focused_entity: ResMut<FocusedEntity>,
+    mut camera_target: ResMut<CameraTarget>,
+    mut follow: ResMut<CameraFollow>,
+) {
+    let saving = actions.pressed(Action::SaveBookmark);
+    for (action, bookmark) in BOOKMARK_ACTIONS.iter().zip(bookmarks.0.iter_mut()) {
+        if !actions.just_pressed(*action) {
+            continue;
+        }
+        if saving {
+            *bookmark = Some(camera.translation.truncate().extend(camera.scale.x));
+        } else if let Some(view) = *bookmark {
+            focused_entity.0 = None;
+            *follow = CameraFollow::Free;
+            camera_target.0 = Some(view);
+        }
+    }
+}
+
+fn follow_hotkeys(
+    actions: Actions,
+    colonies: Res<Colonies>,
+    mut follow: ResMut<CameraFollow>,
+    mut focused_entity: ResMut<FocusedEntity>,
+) {
+    if actions.just_pressed(Action::CycleFollow) {
+        *follow = follow.next(colonies.len());
+        focused_entity.0 = None;
+    }
+}
+
+/// Aim the camera at whatever the follow mode tracks
+fn follow_system(
+    follow: Res<CameraFollow>,
+    settings: Res<CameraSettings>,
+    colonies: Res<Colonies>,
+    time: Res<Time>,
+    camera: Single<(&Transform, &OrthographicProjection), With<Camera2d>>,
+    selected: Query<&Transform, With<Selected>>,
+    ants: Query<(Entity, &Ant, &Transform)>,
+    mut focused_entity: ResMut<FocusedEntity>,
+    mut camera_target: ResMut<CameraTarget>,
+    mut since_cycle: Local<f32>,
+) {
+    let (camera_transform, projection) = *camera;
+    let zoom = camera_transform.scale.x;
+    let is_forager =
+        |ant: &Ant| ant.desired_target() == DesiredTarget::FOOD || ant.carrying() > 0.0;
+
+    match *follow {
+        CameraFollow::Free => {}
+        CameraFollow::Selection => {
+            let count = selected.iter().len();
+            if count > 0 {
+                let centroid = selected
+                    .iter()
+                    .map(|transform| transform.translation.truncate())
+                    .sum::<Vec2>()
+                    / count as f32;
+                camera_target.0 = Some(centroid.extend(zoom));
+            }
+        }
+        CameraFollow::Nest(colony) => {
+            if colony.0 < colonies.len() {
+                camera_target.0 = Some(colonies.get(colony).nest_position.extend(zoom));
+            }
+        }
+        CameraFollow::Foragers => {
+            // The focused ant drives the target, only pick the next one here
+            *since_cycle += time.delta_secs();
+            if focused_entity.0.is_some() && *since_cycle < CAMERA_CYCLE_INTERVAL {
+                return;
+            }
+            *since_cycle = 0.0;
+            let mut foragers = ants
+                .iter()
+                .filter(|(_, ant, _)| is_forager(ant))
+                .map(|(entity, _, _)| entity)
+                .collect::<Vec<_>>();
+            foragers.sort();
+            // The first forager after the current one, wrapping around
+            let current = focused_entity.0;
+            if let Some(entity) = foragers
+                .iter()
+                .find(|entity| current.is_none_or(|current| **entity > current))
+                .or(foragers.first())
+            {
+                focused_entity.0 = Some(*entity);
+            }
+        }
+        CameraFollow::Director => {
+            let positions = ants
+                .iter()
+                .filter(|(_, ant, _)| is_forager(ant))
+                .map(|(_, _, transform)| transform.translation.truncate())
+                .collect::<Vec<_>>();
+            if positions.is_empty() {
+                return;
+            }
+            // Frame the bulk of the foragers, two standard deviations around their centroid
+            let count = positions.len() as f32;
+            let centroid = positions.iter().sum::<Vec2>() / count;
+            let variance = positions
+                .iter()
+                .map(|position| (*position - centroid).powf(2.0))
+                .sum::<Vec2>()
+                / count;
+            let extent = 4.0 * variance.map(f32::sqrt) + 2.0 * CAMERA_DIRECTOR_PADDING;
+            let scale = settings.clamp_scale((extent / projection.area.size()).max_element());
+            camera_target.0 = Some(centroid.extend(scale));
+        }
+    }
+}
+
+/// Keep the zoom within its limits and the view over the world
+fn clamp_camera_system(
+    mut camera_query: Query<(&mut Transform, &OrthographicProjection), With<Camera2d>>,
+    settings: Res<CameraSettings>,
+) {
+    let (mut camera_transform, projection) = camera_query.single_mut();
+    let scale = settings.clamp_scale(camera_transform.scale.x);
+    let position = settings.clamp_position(
+        camera_transform.translation.truncate(),
+        projection.area.half_size() * scale,
+    );
+    // Only write when needed to keep change detection meaningful
+    if scale != camera_transform.scale.x || scale != camera_transform.scale.y {
+        camera_transform.scale = Vec3::new(scale, scale, camera_transform.scale.z);
+    }
+    if position != camera_transform.translation.truncate() {
+        camera_transform.translation = position.extend(camera_transform.translation.z);
     }
 }
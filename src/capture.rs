@@ -0,0 +1,211 @@
+use std::{fs, io::Write, path::PathBuf};
+
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{save_to_disk, Screenshot},
+};
+
+use crate::{
+    bindings::{Action, Actions},
+    colony::Colonies,
+    rng::SimulationRng,
+    CAPTURE_DIRECTORY,
+};
+
+/// Screenshots and frame sequences, configured from the command line:
+/// `--capture-dir <dir>`, `--capture-every <ticks>` and `--screenshot-at <tick>`.
+/// `captures.csv` records the seed of the run, which doesn't replay it on its own
+#[derive(Debug, Clone, Default)]
+pub struct CapturePlugin {
+    pub settings: CaptureSettings,
+}
+
+impl CapturePlugin {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            settings: CaptureSettings::from_args(args),
+        }
+    }
+}
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .init_resource::<SimulationTick>()
+            .add_systems(First, advance_tick)
+            .add_systems(Update, (capture_hotkeys, capture_frames).chain());
+    }
+}
+
+/// Number of simulation steps run so far, it stops while the virtual time is paused
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationTick(pub u64);
+
+#[derive(Resource, Debug, Clone)]
+pub struct CaptureSettings {
+    pub directory: PathBuf,
+    /// Save a frame every this many ticks while recording
+    pub every: u64,
+    pub recording: bool,
+    /// Tick to take a single screenshot at
+    pub screenshot_at: Option<u64>,
+    /// Frames saved by the current recording
+    frames: u64,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from(CAPTURE_DIRECTORY),
+            every: 10,
+            recording: false,
+            screenshot_at: None,
+            frames: 0,
+        }
+    }
+}
+
+impl CaptureSettings {
+    /// Read the capture options, ignoring any other argument
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut settings = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--capture-dir" => {
+                    if let Some(directory) = args.next() {
+                        settings.directory = PathBuf::from(directory);
+                    }
+                }
+                "--capture-every" => {
+                    if let Some(every) = args.next().and_then(|every| every.parse().ok()) {
+                        settings.every = u64::max(every, 1);
+                        settings.recording = true;
+                    }
+                }
+                "--screenshot-at" => {
+                    settings.screenshot_at = args.next().and_then(|tick| tick.parse().ok());
+                }
+                _ => {}
+            }
+        }
+        settings
+    }
+}
+
+fn advance_tick(mut tick: ResMut<SimulationTick>, time: Res<Time<Virtual>>) {
+    if !time.is_paused() {
+        tick.0 += 1;
+    }
+}
+
+/// F12 saves a screenshot, F11 starts or stops recording frames
+fn capture_hotkeys(
+    mut commands: Commands,
+    actions: Actions,
+    mut settings: ResMut<CaptureSettings>,
+    tick: Res<SimulationTick>,
+    time: Res<Time<Virtual>>,
+    colonies: Res<Colonies>,
+    rng: Res<SimulationRng>,
+) {
+    if actions.just_pressed(Action::Screenshot) {
+        let file = format!("screenshot_tick{:08}.png", tick.0);
+        capture(
+            &mut commands,
+            &settings,
+            file,
+            &tick,
+            &time,
+            &colonies,
+            &rng,
+        );
+    }
+    if actions.just_pressed(Action::ToggleRecording) {
+        settings.recording = !settings.recording;
+        settings.frames = 0;
+    }
+}
+
+fn capture_frames(
+    mut commands: Commands,
+    mut settings: ResMut<CaptureSettings>,
+    tick: Res<SimulationTick>,
+    time: Res<Time<Virtual>>,
+    colonies: Res<Colonies>,
+    rng: Res<SimulationRng>,
+) {
+    // Only capture on ticks that advanced the simulation
+    if !tick.is_changed() {
+        return;
+    }
+    if settings.screenshot_at == Some(tick.0) {
+        let file = format!("screenshot_tick{:08}.png", tick.0);
+        capture(
+            &mut commands,
+            &settings,
+            file,
+            &tick,
+            &time,
+            &colonies,
+            &rng,
+        );
+    }
+    if settings.recording && tick.0.is_multiple_of(settings.every) {
+        let file = format!("frame_{:06}_tick{:08}.png", settings.frames, tick.0);
+        capture(
+            &mut commands,
+            &settings,
+            file,
+            &tick,
+            &time,
+            &colonies,
+            &rng,
+        );
+        settings.frames += 1;
+    }
+}
+
+/// Save the primary window and describe the capture in `captures.csv` next to it
+fn capture(
+    commands: &mut Commands,
+    settings: &CaptureSettings,
+    file: String,
+    tick: &SimulationTick,
+    time: &Time<Virtual>,
+    colonies: &Colonies,
+    rng: &SimulationRng,
+) {
+    if let Err(error) = fs::create_dir_all(&settings.directory) {
+        error!("Can't create {:?}: {error}", settings.directory);
+        return;
+    }
+
+    let metadata = settings.directory.join("captures.csv");
+    let write_header = !metadata.exists();
+    let population: usize = colonies.iter().map(|(_, colony)| colony.population).sum();
+    let food_stored: f32 = colonies.iter().map(|(_, colony)| colony.food_stored).sum();
+    let result = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&metadata)
+        .and_then(|mut csv| {
+            if write_header {
+                writeln!(csv, "file,tick,seed,seconds,population,food_stored")?;
+            }
+            writeln!(
+                csv,
+                "{file},{},{},{:.3},{population},{food_stored}",
+                tick.0,
+                rng.seed(),
+                time.elapsed_secs()
+            )
+        });
+    if let Err(error) = result {
+        error!("Can't write {metadata:?}: {error}");
+    }
+
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(settings.directory.join(file)));
+}
@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+
+use crate::{ANT_CARRY_CAPACITY, CASTE_RATIOS};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Caste {
+    Worker,
+    Scout,
+    Soldier,
+}
+
+impl Caste {
+    pub const ALL: [Caste; 3] = [Caste::Worker, Caste::Scout, Caste::Soldier];
+
+    pub fn index(&self) -> usize {
+        match self {
+            Caste::Worker => 0,
+            Caste::Scout => 1,
+            Caste::Soldier => 2,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Caste::Worker => "Worker",
+            Caste::Scout => "Scout",
+            Caste::Soldier => "Soldier",
+        }
+    }
+}
+
+/// How a caste differs from the base `AntSettings`, multipliers unless stated otherwise
+#[derive(Debug, Clone)]
+pub struct CasteProfile {
+    pub speed: f32,
+    pub view_distance: f32,
+    pub view_angle: f32,
+    /// Scales the pheromone levels the ant smells, zero ignores trails
+    pub pheromone_sensitivity: f32,
+    /// Food taken from a source at once, zero never forages
+    pub carrying_capacity: f32,
+    /// Scales the pheromone laid while walking
+    pub deposit: f32,
+    /// Scales the random turns of a wandering ant
+    pub exploration: f32,
+    /// Distance from the nest the ant stays within, if any
+    pub guard_radius: Option<f32>,
+    /// Scales the damage dealt in fights
+    pub attack: f32,
+    /// Charges predators instead of fleeing them
+    pub defends: bool,
+    /// Sprite scale
+    pub size: f32,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct CasteSettings {
+    pub profiles: [CasteProfile; 3],
+    /// Share of each caste a nest aims for, indexed by `Caste::index`
+    pub ratios: [f32; 3],
+}
+
+impl Default for CasteSettings {
+    fn default() -> Self {
+        Self {
+            profiles: [
+                // Worker
+                CasteProfile {
+                    speed: 1.0,
+                    view_distance: 1.0,
+                    view_angle: 1.0,
+                    pheromone_sensitivity: 1.0,
+                    carrying_capacity: ANT_CARRY_CAPACITY,
+                    deposit: 1.0,
+                    exploration: 1.0,
+                    guard_radius: None,
+                    attack: 1.0,
+                    defends: false,
+                    size: 1.0,
+                },
+                // Scout: fast and far sighted, mostly ignores trails but lays strong ones
+                CasteProfile {
+                    speed: 1.3,
+                    view_distance: 1.5,
+                    view_angle: 1.3,
+                    pheromone_sensitivity: 0.3,
+                    carrying_capacity: ANT_CARRY_CAPACITY * 0.5,
+                    deposit: 2.0,
+                    exploration: 2.0,
+                    guard_radius: None,
+                    attack: 0.5,
+                    defends: false,
+                    size: 0.8,
+                },
+                // Soldier: stays around the nest, bites hard and takes on predators
+                CasteProfile {
+                    speed: 0.8,
+                    view_distance: 1.0,
+                    view_angle: 0.8,
+                    pheromone_sensitivity: 0.0,
+                    carrying_capacity: 0.0,
+                    deposit: 0.0,
+                    exploration: 0.5,
+                    guard_radius: Some(300.0),
+                    attack: 3.0,
+                    defends: true,
+                    size: 1.4,
+                },
+            ],
+            ratios: CASTE_RATIOS,
+        }
+    }
+}
+
+impl CasteSettings {
+    pub fn profile(&self, caste: Caste) -> &CasteProfile {
+        &self.profiles[caste.index()]
+    }
+
+    /// Caste that is the furthest below its ratio given the current head count
+    pub fn next_caste(&self, counts: [usize; 3]) -> Caste {
+        let total = counts.iter().sum::<usize>() as f32 + 1.0;
+        let total_ratio: f32 = self.ratios.iter().sum();
+        if total_ratio <= 0.0 {
+            return Caste::Worker;
+        }
+        Caste::ALL
+            .into_iter()
+            .max_by(|a, b| {
+                let deficit = |caste: &Caste| {
+                    self.ratios[caste.index()] / total_ratio * total - counts[caste.index()] as f32
+                };
+                deficit(a).total_cmp(&deficit(b))
+            })
+            .unwrap_or(Caste::Worker)
+    }
+}
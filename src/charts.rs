@@ -0,0 +1,294 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+use crate::{
+    ant::{Ant, DesiredTarget},
+    colony::{Colonies, ColonyId},
+    ui::UiControls,
+    CHART_HEIGHT, CHART_HISTORY_LEN, CHART_SAMPLE_INTERVAL, CHART_WIDTH,
+};
+
+pub struct ChartsPlugin;
+
+impl Plugin for ChartsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChartHistory>()
+            .add_systems(Startup, setup)
+            .add_systems(Update, (sample_history, draw_charts).chain());
+    }
+}
+
+/// Values of the simulation at one point in time
+#[derive(Debug, Clone, Default)]
+pub struct ChartSample {
+    /// Per colony
+    pub population: Vec<f32>,
+    /// Per colony
+    pub food_stored: Vec<f32>,
+    /// Ants carrying food or heading to food, per colony
+    pub foragers: Vec<f32>,
+    /// Ants in each state, indexed by `DesiredTarget::index`
+    pub targets: [f32; DesiredTarget::ALL.len()],
+}
+
+/// Rolling window of samples, the oldest first
+#[derive(Resource, Debug)]
+pub struct ChartHistory {
+    timer: Timer,
+    samples: VecDeque<ChartSample>,
+}
+
+impl Default for ChartHistory {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(CHART_SAMPLE_INTERVAL, TimerMode::Repeating),
+            samples: VecDeque::with_capacity(CHART_HISTORY_LEN),
+        }
+    }
+}
+
+impl ChartHistory {
+    pub fn samples(&self) -> impl Iterator<Item = &ChartSample> {
+        self.samples.iter()
+    }
+
+    fn push(&mut self, sample: ChartSample) {
+        if self.samples.len() >= CHART_HISTORY_LEN {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum Chart {
+    Population,
+    FoodStored,
+    Foragers,
+    Targets,
+}
+
+impl Chart {
+    const ALL: [Chart; 4] = [
+        Chart::Population,
+        Chart::FoodStored,
+        Chart::Foragers,
+        Chart::Targets,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Chart::Population => "Population",
+            Chart::FoodStored => "Food stored",
+            Chart::Foragers => "Active foragers",
+            Chart::Targets => "Ants per target",
+        }
+    }
+
+    fn values<'a>(&self, sample: &'a ChartSample) -> &'a [f32] {
+        match self {
+            Chart::Population => &sample.population,
+            Chart::FoodStored => &sample.food_stored,
+            Chart::Foragers => &sample.foragers,
+            Chart::Targets => &sample.targets,
+        }
+    }
+
+    /// Colour of each line, the colony colours or one per target
+    fn colors(&self, colonies: &Colonies) -> Vec<[f32; 4]> {
+        match self {
+            Chart::Targets => DesiredTarget::ALL
+                .iter()
+                .map(DesiredTarget::color)
+                .collect(),
+            _ => colonies.iter().map(|(_, colony)| colony.color).collect(),
+        }
+    }
+}
+
+/// Holds the charts, shown when `UiControls::show_charts` is set
+#[derive(Component)]
+struct ChartsPanel;
+
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let font = TextFont {
+        font_size: 12.0,
+        ..default()
+    };
+
+    // Full width row to center the panel at the bottom of the window
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ChartsPanel,
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Node {
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::auto(2),
+                    column_gap: Val::Px(6.0),
+                    row_gap: Val::Px(4.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::BLACK.with_alpha(0.75)),
+                GlobalZIndex(i32::MAX),
+                Interaction::default(),
+            ))
+            .with_children(|p| {
+                for chart in Chart::ALL {
+                    let image = Image::new_fill(
+                        Extent3d {
+                            width: CHART_WIDTH,
+                            height: CHART_HEIGHT,
+                            depth_or_array_layers: 1,
+                        },
+                        TextureDimension::D2,
+                        &[0, 0, 0, 0],
+                        TextureFormat::Rgba8UnormSrgb,
+                        RenderAssetUsages::default(),
+                    );
+                    p.spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    })
+                    .with_children(|p| {
+                        p.spawn((Text::new(chart.title()), font.clone()));
+                        p.spawn((
+                            Node {
+                                width: Val::Px(CHART_WIDTH as f32),
+                                height: Val::Px(CHART_HEIGHT as f32),
+                                ..default()
+                            },
+                            ImageNode::new(images.add(image)),
+                            chart,
+                        ));
+                    });
+                }
+            });
+        });
+}
+
+fn sample_history(
+    mut history: ResMut<ChartHistory>,
+    time: Res<Time>,
+    colonies: Res<Colonies>,
+    ants: Query<(&Ant, &ColonyId)>,
+) {
+    if !history
+        .bypass_change_detection()
+        .timer
+        .tick(time.delta())
+        .just_finished()
+    {
+        return;
+    }
+
+    let mut sample = ChartSample {
+        population: colonies
+            .iter()
+            .map(|(_, colony)| colony.population as f32)
+            .collect(),
+        food_stored: colonies
+            .iter()
+            .map(|(_, colony)| colony.food_stored)
+            .collect(),
+        foragers: vec![0.0; colonies.len()],
+        targets: [0.0; DesiredTarget::ALL.len()],
+    };
+    for (ant, colony) in ants.iter() {
+        let target = ant.desired_target();
+        if ant.carrying() > 0.0 || target == DesiredTarget::FOOD {
+            sample.foragers[colony.0] += 1.0;
+        }
+        sample.targets[target.index()] += 1.0;
+    }
+    history.push(sample);
+}
+
+fn draw_charts(
+    history: Res<ChartHistory>,
+    colonies: Res<Colonies>,
+    ui_controls: Res<UiControls>,
+    mut panel: Single<&mut Visibility, With<ChartsPanel>>,
+    charts: Query<(&ImageNode, &Chart)>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if ui_controls.is_changed() {
+        **panel = if ui_controls.show_charts {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    if !ui_controls.show_charts || !history.is_changed() {
+        return;
+    }
+
+    for (image_node, chart) in charts.iter() {
+        let Some(image) = images.get_mut(&image_node.image) else {
+            continue;
+        };
+        image.data.fill(0);
+
+        // Scale every line of the chart to the highest value in the window
+        let highest = history
+            .samples()
+            .flat_map(|sample| chart.values(sample).iter().copied())
+            .fold(1.0, f32::max);
+        let step = (CHART_WIDTH - 1) as f32 / (CHART_HISTORY_LEN - 1) as f32;
+        let to_pixel = |i: usize, value: f32| {
+            IVec2::new(
+                (i as f32 * step) as i32,
+                ((1.0 - value / highest) * (CHART_HEIGHT - 1) as f32) as i32,
+            )
+        };
+
+        for (line, color) in chart.colors(&colonies).into_iter().enumerate() {
+            let [r, g, b, _] = color;
+            let color = Color::linear_rgb(r, g, b).to_srgba().to_u8_array();
+            let points: Vec<IVec2> = history
+                .samples()
+                .enumerate()
+                .filter_map(|(i, sample)| {
+                    chart
+                        .values(sample)
+                        .get(line)
+                        .map(|value| to_pixel(i, *value))
+                })
+                .collect();
+            for segment in points.windows(2) {
+                draw_line(&mut image.data, segment[0], segment[1], color);
+            }
+        }
+    }
+}
+
+/// Plot a line between two pixels of a chart image
+fn draw_line(data: &mut [u8], from: IVec2, to: IVec2, color: [u8; 4]) {
+    let steps = (to - from).abs().max_element().max(1);
+    for step in 0..=steps {
+        let pixel = from
+            .as_vec2()
+            .lerp(to.as_vec2(), step as f32 / steps as f32);
+        let (x, y) = (pixel.x.round() as u32, pixel.y.round() as u32);
+        if x < CHART_WIDTH && y < CHART_HEIGHT {
+            let index = ((x + y * CHART_WIDTH) * 4) as usize;
+            data[index..index + 4].copy_from_slice(&color);
+        }
+    }
+}
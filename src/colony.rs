@@ -0,0 +1,233 @@
+use bevy::prelude::*;
+
+use crate::{
+    ant::{Ant, AntSettings},
+    caste::Caste,
+    genome::GenePool,
+    ui::UiControls,
+    ANT_COUNT, COLONY_AGGRESSIVE, COLONY_COLORS, COLONY_COUNT, COLONY_NEST_DISTANCE, NEST_POSITION,
+};
+
+/// Colonies founded at startup, configured from the command line:
+/// `--colonies <count>` and `--colony <number>:<ants>[:aggressive|peaceful]`
+#[derive(Debug, Clone, Default)]
+pub struct ColonyPlugin {
+    pub settings: ColonySettings,
+}
+
+impl ColonyPlugin {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            settings: ColonySettings::from_args(args),
+        }
+    }
+}
+
+impl Plugin for ColonyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Colonies::new(&self.settings))
+            .insert_resource(self.settings.clone())
+            .add_systems(Update, count_population)
+            .add_systems(Update, draw_nest);
+    }
+}
+
+/// Index of the colony an ant belongs to in `Colonies`
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColonyId(pub usize);
+
+/// How a colony starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColonyConfig {
+    /// Number of ants spawned at startup
+    pub n_ants: usize,
+    /// Whether its ants fight the rivals they meet
+    pub aggressive: bool,
+}
+
+impl Default for ColonyConfig {
+    fn default() -> Self {
+        Self {
+            n_ants: ANT_COUNT,
+            aggressive: COLONY_AGGRESSIVE,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct ColonySettings {
+    /// One entry per colony
+    pub colonies: Vec<ColonyConfig>,
+}
+
+impl Default for ColonySettings {
+    fn default() -> Self {
+        Self {
+            colonies: vec![ColonyConfig::default(); COLONY_COUNT],
+        }
+    }
+}
+
+impl ColonySettings {
+    /// Read the colony options, ignoring any other argument
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut count = COLONY_COUNT;
+        let mut overrides = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--colonies" => match args.next().and_then(|count| count.parse().ok()) {
+                    Some(colonies) if colonies > 0 => count = colonies,
+                    _ => warn!("--colonies expects a number above 0"),
+                },
+                "--colony" => match args.next().as_deref().and_then(parse_colony) {
+                    Some(colony) => overrides.push(colony),
+                    None => warn!("--colony expects <number>:<ants>[:aggressive|peaceful]"),
+                },
+                _ => {}
+            }
+        }
+
+        let mut settings = Self {
+            colonies: vec![ColonyConfig::default(); count],
+        };
+        for (index, config) in overrides {
+            match settings.colonies.get_mut(index) {
+                Some(colony) => *colony = config,
+                None => warn!("There is no colony {}", index + 1),
+            }
+        }
+        settings
+    }
+}
+
+/// Index and config of a `--colony` value, colonies are numbered from 1 like in the UI
+fn parse_colony(value: &str) -> Option<(usize, ColonyConfig)> {
+    let mut parts = value.split(':');
+    let index = parts.next()?.parse::<usize>().ok()?.checked_sub(1)?;
+    let n_ants = parts.next()?.parse().ok()?;
+    let aggressive = match parts.next() {
+        None => COLONY_AGGRESSIVE,
+        Some("aggressive") => true,
+        Some("peaceful") => false,
+        Some(_) => return None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((index, ColonyConfig { n_ants, aggressive }))
+}
+
+#[derive(Debug, Clone)]
+pub struct Colony {
+    pub nest_position: Vec2,
+    pub color: [f32; 4],
+    /// Number of ants spawned at startup
+    pub n_ants: usize,
+    /// Whether its ants fight the rivals they meet
+    pub aggressive: bool,
+    pub food_stored: f32,
+    pub population: usize,
+    /// Head count of each caste, indexed by `Caste::index`
+    pub caste_population: [usize; 3],
+    pub gene_pool: GenePool,
+}
+
+impl Colony {
+    pub fn new(nest_position: Vec2, color: [f32; 4], config: ColonyConfig) -> Self {
+        Self {
+            nest_position,
+            color,
+            n_ants: config.n_ants,
+            aggressive: config.aggressive,
+            food_stored: 0.0,
+            population: 0,
+            caste_population: [0; 3],
+            gene_pool: GenePool::default(),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct Colonies(pub Vec<Colony>);
+
+impl Colonies {
+    pub fn new(settings: &ColonySettings) -> Self {
+        let center = Vec2::from(NEST_POSITION);
+        let count = settings.colonies.len();
+        if count == 1 {
+            return Self(vec![Colony::new(
+                center,
+                COLONY_COLORS[0],
+                settings.colonies[0],
+            )]);
+        }
+
+        // Spread the nests evenly on a circle around the center of the world
+        Self(
+            settings
+                .colonies
+                .iter()
+                .enumerate()
+                .map(|(i, config)| {
+                    let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                    Colony::new(
+                        center + Vec2::from_angle(angle) * COLONY_NEST_DISTANCE,
+                        COLONY_COLORS[i % COLONY_COLORS.len()],
+                        *config,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, id: ColonyId) -> &Colony {
+        &self.0[id.0]
+    }
+
+    pub fn get_mut(&mut self, id: ColonyId) -> &mut Colony {
+        &mut self.0[id.0]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ColonyId, &Colony)> {
+        self.0.iter().enumerate().map(|(i, c)| (ColonyId(i), c))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+fn count_population(mut colonies: ResMut<Colonies>, ants: Query<(&ColonyId, &Caste), With<Ant>>) {
+    let mut caste_population = vec![[0; 3]; colonies.len()];
+    for (colony_id, caste) in ants.iter() {
+        caste_population[colony_id.0][caste.index()] += 1;
+    }
+    for (colony, caste_population) in colonies.0.iter_mut().zip(caste_population) {
+        colony.population = caste_population.iter().sum();
+        colony.caste_population = caste_population;
+    }
+}
+
+fn draw_nest(
+    mut gizmos: Gizmos,
+    ant_settings: Res<AntSettings>,
+    colonies: Res<Colonies>,
+    ui_controls: Res<UiControls>,
+) {
+    if !ui_controls.show_nests {
+        return;
+    }
+    // Draw the nest of every colony in its tint
+    for (_, colony) in colonies.iter() {
+        gizmos.circle_2d(
+            colony.nest_position,
+            ant_settings.nest_size,
+            LinearRgba::from_f32_array(colony.color),
+        );
+    }
+}
@@ -9,9 +9,38 @@ pub const GRID_RESOLUTION: f32 = 50.0;
 pub const GRID_AREA_SIZE: f32 = 5000.0;
 pub const DEBUG_GRID_COLOR: [f32; 4] = [0.5, 0.5, 0.5, 0.04];
 
-pub const CAMERA_MAX_ZOOM: f32 = 0.5;
-pub const CAMERA_MIN_ZOOM: f32 = 5.0;
-pub const CAMERA_FOCUS_RANGE: f32 = 20.0;
+pub const CAMERA_MIN_SCALE: f32 = 0.5; // Closest zoom, the farthest is fitted to the world
+pub const CAMERA_BOUNDS_MARGIN: f32 = 500.0; // World units the view can go past the edges
+pub const CAMERA_PICK_RADIUS: f32 = 12.0; // Pixels around the cursor
+pub const CAMERA_PAN_SPEED: f32 = 800.0; // Keyboard panning, in pixels per second
+pub const CAMERA_SMOOTHING: f32 = 5.0; // Rate the camera catches up with its target
+pub const CAMERA_DIRECTOR_SMOOTHING: f32 = 0.8;
+pub const CAMERA_DIRECTOR_PADDING: f32 = 300.0; // World units around the framed ants
+pub const CAMERA_CYCLE_INTERVAL: f32 = 5.0; // Seconds on each forager
+
+// Minimap
+pub const MINIMAP_SIZE: f32 = 200.0; // Width in pixels
+
+// Charts
+pub const CHART_SAMPLE_INTERVAL: f32 = 0.5; // Seconds between samples
+pub const CHART_HISTORY_LEN: usize = 120;
+pub const CHART_WIDTH: u32 = 160;
+pub const CHART_HEIGHT: u32 = 60;
+
+// World editing tools
+pub const TOOL_BRUSH_RADIUS: f32 = 100.0;
+pub const TOOL_FOOD_AMOUNT: f32 = 20.0; // Food in each painted source
+pub const WALL_COLOR: [f32; 4] = [0.35, 0.3, 0.25, 1.0];
+
+// Trails of tracked ants
+pub const TRAIL_LENGTH: usize = 300; // Points kept per ant
+pub const TRAIL_SPACING: f32 = 5.0; // Distance walked between points
+
+// Input
+pub const BINDINGS_FILE: &str = "bindings.cfg";
+
+// Captures
+pub const CAPTURE_DIRECTORY: &str = "captures";
 
 pub const SPRITE_ANT: &str = "ant.png";
 
@@ -19,16 +48,82 @@ pub const NEST_SIZE: f32 = 100.0;
 pub const NEST_POSITION: (f32, f32) = (0.0, 0.0);
 pub const NEST_COLOR: [f32; 4] = [1.0, 0.65, 0.0, 1.0];
 
+pub const COLONY_COUNT: usize = 2;
+pub const COLONY_NEST_DISTANCE: f32 = 1500.0;
+pub const COLONY_AGGRESSIVE: bool = true;
+pub const COLONY_MAX_POPULATION: usize = 300;
+pub const COLONY_COLORS: [[f32; 4]; 4] = [
+    NEST_COLOR,
+    [0.3, 0.6, 1.0, 1.0],
+    [0.8, 0.3, 0.9, 1.0],
+    [0.9, 0.9, 0.3, 1.0],
+];
+
+pub const FOOD_SOURCE_COUNT: usize = 20;
+pub const FOOD_SOURCE_AMOUNT: f32 = 100.0;
+pub const FOOD_COLOR: [f32; 4] = [0.2, 0.9, 0.2, 1.0];
+pub const FOOD_FOCUS_ZOOM: f32 = 1.5; // Camera scale when a food source is focused
+pub const FOOD_REGROWTH_RATE: f32 = 0.02; // Share of a source growing back each second on grass
+pub const FOOD_SPAWN_INTERVAL: f32 = 15.0; // Seconds between new sources at average abundance
+pub const FOOD_MAX_SOURCES: usize = 40; // No new sources spawn past this count
+pub const FOOD_YEAR_LENGTH: f32 = 240.0; // Seconds for a full cycle of the seasons
+pub const FOOD_SEASON_AMPLITUDE: f32 = 0.8; // How far abundance swings around its average
+
 pub const ANT_COUNT: usize = 100;
 pub const ANT_VIEW_DISTANCE: f32 = 150.0;
 pub const ANT_SIZE: f32 = 25.0;
 pub const ANT_VIEW_ANGLE: f32 = (PI / 2.0) as f32;
+pub const ANT_MIN_VISIBLE_FRACTION: f32 = 0.25; // Share of another ant that must be in view to see it
+pub const VIEW_CONE_SAMPLES: usize = 32; // Points sampled to measure partial visibility
 pub const ANT_SPEED: f32 = 100.0;
 pub const ANT_ROTATION_SPEED: f32 = 1.0;
+pub const ANT_CONTACT_DISTANCE: f32 = ANT_SIZE;
+pub const ANT_SEPARATION_DISTANCE: f32 = ANT_SIZE * 1.5;
+pub const ANT_SEPARATION_WEIGHT: f32 = 0.5;
+pub const ANT_SENSOR_ANGLE: f32 = (PI / 6.0) as f32;
+pub const ANT_SENSOR_DISTANCE: f32 = 40.0;
+pub const ANT_SCENT_RADIUS: f32 = 100.0; // Smell works all around, but not far
+pub const ANT_SCENT_FALLOFF: f32 = 2.0; // Exponent of the drop in strength with distance
+pub const ANT_SCENT_WEIGHT: f32 = 0.3; // Share of the scent when blended with vision
+pub const ANT_SCENT_THRESHOLD: f32 = 0.05; // Weakest smell an ant reacts to
+pub const ANT_CARRY_CAPACITY: f32 = 1.0;
+pub const ANT_ENERGY: f32 = 100.0;
+pub const ANT_FIGHT_DAMAGE: f32 = 20.0;
+pub const ANT_SPAWN_COST: f32 = 5.0;
+pub const ANT_ALARM_DEPOSIT: f32 = 4.0; // Alarm laid with a predator in sight, relative to trails
+pub const ANT_ALARM_THRESHOLD: f32 = 0.1; // Weakest alarm an ant reacts to
+
+// Predators
+pub const PREDATOR_COUNT: usize = 2;
+pub const PREDATOR_SIZE: f32 = 50.0;
+pub const PREDATOR_SPEED: f32 = 60.0; // While wandering
+pub const PREDATOR_CHASE_SPEED: f32 = 120.0; // Faster than workers, slower than scouts
+pub const PREDATOR_ROTATION_SPEED: f32 = 2.0;
+pub const PREDATOR_VIEW_DISTANCE: f32 = 250.0;
+pub const PREDATOR_VIEW_ANGLE: f32 = (PI * 2.0 / 3.0) as f32;
+pub const PREDATOR_HEALTH: f32 = 300.0; // Worn down by soldier bites
+pub const PREDATOR_EAT_TIME: f32 = 2.0; // Seconds spent on a catch before hunting again
+pub const PREDATOR_RESPAWN_INTERVAL: f32 = 60.0; // Seconds before a killed predator is replaced
+pub const PREDATOR_COLOR: [f32; 4] = [0.9, 0.3, 0.1, 1.0];
+
+/// Share of workers, scouts and soldiers a nest aims for
+pub const CASTE_RATIOS: [f32; 3] = [0.7, 0.15, 0.15];
+
+/// Largest relative change of a trait between parent and offspring
+pub const GENOME_MUTATION: f32 = 0.1;
+/// Number of successful forager genomes a colony breeds from
+pub const GENOME_POOL_SIZE: usize = 20;
 
 pub const DEBUG_ANT_VIEW_COLOR: [f32; 4] = [0.0, 1.0, 0.0, 0.5];
 pub const DEBUG_ANT_VIEW_RADIUS_COLOR: [f32; 4] = [0.55, 0.55, 0.55, 0.2];
 pub const DEBUG_ANT_VIEW_COLOR_ALERT: [f32; 4] = [1.0, 0.0, 0.0, 0.5];
 
-pub const PHEROMONE_DECAY: f32 = 0.99;
+pub const PHEROMONE_DECAY: f32 = 0.99; // Share of a trail kept each second, not each frame
+pub const PHEROMONE_DIFFUSION: f32 = 0.1; // Share of a cell spreading to its neighbours each second
+pub const PHEROMONE_ALARM_DECAY: f32 = 0.7; // Share of alarm kept each second
 pub const PHEROMONE_MAX: f32 = 1.0;
+pub const PHEROMONE_DEPOSIT: f32 = 0.5;
+pub const PHEROMONE_FOLLOW_THRESHOLD: f32 = 0.05;
+pub const PHEROMONE_DRAW_THRESHOLD: f32 = 0.02;
+pub const PHEROMONE_HEATMAP_INTENSITY: f32 = 1.0;
+pub const PHEROMONE_HEATMAP_OPACITY: f32 = 0.6;
@@ -0,0 +1,231 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    ant::AntSettings,
+    camera::FocusableEntity,
+    colony::Colonies,
+    grid::{Grid, GridEntity, GridEntityType},
+    rng::SimulationRng,
+    FOOD_COLOR, FOOD_FOCUS_ZOOM, FOOD_MAX_SOURCES, FOOD_REGROWTH_RATE, FOOD_SEASON_AMPLITUDE,
+    FOOD_SOURCE_AMOUNT, FOOD_SOURCE_COUNT, FOOD_SPAWN_INTERVAL, FOOD_YEAR_LENGTH,
+};
+
+pub struct FoodPlugin;
+
+impl Plugin for FoodPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FoodSettings>()
+            .init_resource::<Seasons>()
+            .add_systems(Startup, spawn_food)
+            .add_systems(
+                Update,
+                (advance_seasons, regrow_food, spawn_seasonal_food).chain(),
+            )
+            .add_systems(Update, despawn_empty_food)
+            .add_systems(Update, draw_food);
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct FoodSource {
+    pub amount: f32,
+    /// Amount the source grows back to
+    pub capacity: f32,
+}
+
+impl FoodSource {
+    pub fn new(amount: f32) -> Self {
+        Self {
+            amount,
+            capacity: amount,
+        }
+    }
+
+    /// Radius of the source, its area follows the amount left
+    pub fn radius(&self) -> f32 {
+        (self.amount / FOOD_SOURCE_AMOUNT).sqrt() * 40.0
+    }
+
+    /// Remove up to `amount` food from the source and return what was taken
+    pub fn take(&mut self, amount: f32) -> f32 {
+        let taken = amount.min(self.amount);
+        self.amount -= taken;
+        taken
+    }
+
+    /// Grow back up to the capacity, `rate` is the share of the capacity added each second
+    pub fn regrow(&mut self, rate: f32, delta_secs: f32) {
+        self.amount = (self.amount + self.capacity * rate * delta_secs).min(self.capacity);
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct FoodSettings {
+    /// Share of a source growing back each second on grass, at average abundance
+    pub regrowth_rate: f32,
+    /// Seconds between new random sources at average abundance
+    pub spawn_interval: f32,
+    pub max_sources: usize,
+    /// Seconds for a full cycle of the seasons
+    pub year_length: f32,
+    /// How far abundance swings around its average, 0 turns the seasons off
+    pub season_amplitude: f32,
+}
+
+impl Default for FoodSettings {
+    fn default() -> Self {
+        Self {
+            regrowth_rate: FOOD_REGROWTH_RATE,
+            spawn_interval: FOOD_SPAWN_INTERVAL,
+            max_sources: FOOD_MAX_SOURCES,
+            year_length: FOOD_YEAR_LENGTH,
+            season_amplitude: FOOD_SEASON_AMPLITUDE,
+        }
+    }
+}
+
+/// Simulated time of year, food is plentiful in summer and scarce in winter
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Seasons {
+    /// Seconds since the start of the year, which starts in spring
+    pub time_of_year: f32,
+    /// Progress toward the next random source, one spawns at 1
+    spawn_progress: f32,
+}
+
+impl Seasons {
+    /// Position in the year, from 0 to 1
+    pub fn phase(&self, settings: &FoodSettings) -> f32 {
+        (self.time_of_year / settings.year_length).fract()
+    }
+
+    /// Multiplier of regrowth and spawning, averaging 1 over the year, highest in
+    /// midsummer and lowest in midwinter
+    pub fn abundance(&self, settings: &FoodSettings) -> f32 {
+        1.0 + settings.season_amplitude * ((self.phase(settings) - 0.125) * TAU).sin()
+    }
+
+    pub fn name(&self, settings: &FoodSettings) -> &'static str {
+        ["spring", "summer", "autumn", "winter"][(self.phase(settings) * 4.0) as usize % 4]
+    }
+}
+
+fn advance_seasons(mut seasons: ResMut<Seasons>, settings: Res<FoodSettings>, time: Res<Time>) {
+    seasons.time_of_year = (seasons.time_of_year + time.delta_secs()) % settings.year_length;
+}
+
+/// Random position for a new source, away from the nests and where food can grow
+fn random_food_position(
+    grid: &Grid,
+    colonies: &Colonies,
+    nest_size: f32,
+    rng: &mut SimulationRng,
+) -> Option<Vec2> {
+    let (min, max) = grid.get_boundaries();
+    // Give up on maps with hardly any fertile ground
+    (0..100).find_map(|_| {
+        let position = Vec2::new(
+            min.x + rng.gen::<f32>() * (max.x - min.x),
+            min.y + rng.gen::<f32>() * (max.y - min.y),
+        );
+        // Keep food away from the nests so ants have to look for it
+        let near_nest = colonies
+            .iter()
+            .any(|(_, colony)| grid.distance(position, colony.nest_position) < nest_size * 4.0);
+        let fertile = !grid.is_wall_at(position) && grid.terrain_at(position).food_regrowth() > 0.0;
+        (!near_nest && fertile).then_some(position)
+    })
+}
+
+fn spawn_food_source(commands: &mut Commands, grid: &Grid, position: Vec2, amount: f32) {
+    commands.spawn((
+        Transform::from_translation(position.extend(0.0)),
+        FoodSource::new(amount),
+        FocusableEntity::new(FOOD_FOCUS_ZOOM),
+        GridEntity::new(grid.get_grid_pos(position), GridEntityType::Food),
+    ));
+}
+
+fn spawn_food(
+    mut commands: Commands,
+    grid: Res<Grid>,
+    colonies: Res<Colonies>,
+    ant_settings: Res<AntSettings>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    for _ in 0..FOOD_SOURCE_COUNT {
+        if let Some(position) =
+            random_food_position(&grid, &colonies, ant_settings.nest_size, &mut rng)
+        {
+            spawn_food_source(&mut commands, &grid, position, FOOD_SOURCE_AMOUNT);
+        }
+    }
+}
+
+fn regrow_food(
+    mut food_sources: Query<(&Transform, &mut FoodSource)>,
+    grid: Res<Grid>,
+    settings: Res<FoodSettings>,
+    seasons: Res<Seasons>,
+    time: Res<Time>,
+) {
+    let rate = settings.regrowth_rate * seasons.abundance(&settings);
+    for (transform, mut food) in food_sources.iter_mut() {
+        // Emptied sources are gone for good, new ones spawn elsewhere
+        if food.amount <= 0.0 || food.amount >= food.capacity {
+            continue;
+        }
+        let terrain = grid.terrain_at(transform.translation.truncate());
+        food.regrow(rate * terrain.food_regrowth(), time.delta_secs());
+    }
+}
+
+/// New sources appear more often and larger when food is abundant
+fn spawn_seasonal_food(
+    mut commands: Commands,
+    mut seasons: ResMut<Seasons>,
+    food_sources: Query<(), With<FoodSource>>,
+    grid: Res<Grid>,
+    colonies: Res<Colonies>,
+    ant_settings: Res<AntSettings>,
+    settings: Res<FoodSettings>,
+    time: Res<Time>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    let abundance = seasons.abundance(&settings);
+    seasons.spawn_progress += time.delta_secs() * abundance / settings.spawn_interval;
+    if seasons.spawn_progress < 1.0 {
+        return;
+    }
+    seasons.spawn_progress -= 1.0;
+
+    if food_sources.iter().count() >= settings.max_sources {
+        return;
+    }
+    if let Some(position) = random_food_position(&grid, &colonies, ant_settings.nest_size, &mut rng)
+    {
+        let amount = FOOD_SOURCE_AMOUNT * abundance.clamp(0.2, 1.0);
+        spawn_food_source(&mut commands, &grid, position, amount);
+    }
+}
+
+fn despawn_empty_food(mut commands: Commands, food_sources: Query<(Entity, &FoodSource)>) {
+    for (entity, food) in food_sources.iter() {
+        if food.amount <= 0.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn draw_food(mut gizmos: Gizmos, food_sources: Query<(&Transform, &FoodSource)>) {
+    for (transform, food) in food_sources.iter() {
+        gizmos.circle_2d(
+            transform.translation.truncate(),
+            food.radius(),
+            LinearRgba::from_f32_array(FOOD_COLOR),
+        );
+    }
+}
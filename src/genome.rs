@@ -0,0 +1,133 @@
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    prelude::*,
+};
+
+use rand::Rng;
+
+use crate::{ant::Ant, GENOME_MUTATION, GENOME_POOL_SIZE};
+
+pub struct GenomePlugin;
+
+impl Plugin for GenomePlugin {
+    fn build(&self, app: &mut App) {
+        for (mean, std_dev) in Genome::DIAGNOSTICS {
+            app.register_diagnostic(Diagnostic::new(mean))
+                .register_diagnostic(Diagnostic::new(std_dev));
+        }
+        app.add_systems(Update, measure_genomes);
+    }
+}
+
+/// Heritable traits of an ant, passed down by the nest to the ants it spawns
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Genome {
+    /// Multiplier of the view angle
+    pub view_angle: f32,
+    /// Strength of the random turns of a wandering ant
+    pub turn_noise: f32,
+    /// Multiplier of the pheromone laid while walking
+    pub deposit_rate: f32,
+    /// Multiplier of the pheromone levels the ant smells
+    pub trail_follow_weight: f32,
+}
+
+impl Default for Genome {
+    fn default() -> Self {
+        Self {
+            view_angle: 1.0,
+            turn_noise: 0.4,
+            deposit_rate: 1.0,
+            trail_follow_weight: 1.0,
+        }
+    }
+}
+
+impl Genome {
+    pub const VIEW_ANGLE: DiagnosticPath = DiagnosticPath::const_new("genome/view_angle");
+    pub const VIEW_ANGLE_STD: DiagnosticPath = DiagnosticPath::const_new("genome/view_angle_std");
+    pub const TURN_NOISE: DiagnosticPath = DiagnosticPath::const_new("genome/turn_noise");
+    pub const TURN_NOISE_STD: DiagnosticPath = DiagnosticPath::const_new("genome/turn_noise_std");
+    pub const DEPOSIT_RATE: DiagnosticPath = DiagnosticPath::const_new("genome/deposit_rate");
+    pub const DEPOSIT_RATE_STD: DiagnosticPath =
+        DiagnosticPath::const_new("genome/deposit_rate_std");
+    pub const TRAIL_FOLLOW_WEIGHT: DiagnosticPath =
+        DiagnosticPath::const_new("genome/trail_follow_weight");
+    pub const TRAIL_FOLLOW_WEIGHT_STD: DiagnosticPath =
+        DiagnosticPath::const_new("genome/trail_follow_weight_std");
+
+    /// Mean and standard deviation diagnostics, in the order of `Genome::traits`
+    pub const DIAGNOSTICS: [(DiagnosticPath, DiagnosticPath); 4] = [
+        (Self::VIEW_ANGLE, Self::VIEW_ANGLE_STD),
+        (Self::TURN_NOISE, Self::TURN_NOISE_STD),
+        (Self::DEPOSIT_RATE, Self::DEPOSIT_RATE_STD),
+        (Self::TRAIL_FOLLOW_WEIGHT, Self::TRAIL_FOLLOW_WEIGHT_STD),
+    ];
+
+    pub fn traits(&self) -> [f32; 4] {
+        [
+            self.view_angle,
+            self.turn_noise,
+            self.deposit_rate,
+            self.trail_follow_weight,
+        ]
+    }
+
+    /// Copy of the genome with every trait randomly nudged by up to `GENOME_MUTATION`
+    pub fn mutate(&self, rng: &mut impl Rng) -> Self {
+        let mut nudge = |value: f32, min: f32, max: f32| {
+            (value * (1.0 + (rng.gen::<f32>() - 0.5) * 2.0 * GENOME_MUTATION)).clamp(min, max)
+        };
+        Self {
+            view_angle: nudge(self.view_angle, 0.1, 4.0),
+            turn_noise: nudge(self.turn_noise, 0.01, 2.0),
+            deposit_rate: nudge(self.deposit_rate, 0.0, 5.0),
+            trail_follow_weight: nudge(self.trail_follow_weight, 0.0, 5.0),
+        }
+    }
+}
+
+/// Genomes of the ants that brought food back, parents of the next ants
+#[derive(Debug, Clone, Default)]
+pub struct GenePool(Vec<Genome>);
+
+impl GenePool {
+    /// Remember a successful genome, forgetting the oldest once full
+    pub fn add(&mut self, genome: &Genome) {
+        if self.0.len() >= GENOME_POOL_SIZE {
+            self.0.remove(0);
+        }
+        self.0.push(genome.clone());
+    }
+
+    /// Mutated child of a random successful genome, or of the default one
+    pub fn offspring(&self, rng: &mut impl Rng) -> Genome {
+        match self.0.len() {
+            0 => Genome::default().mutate(rng),
+            len => self.0[rng.gen_range(0..len)].mutate(rng),
+        }
+    }
+}
+
+fn measure_genomes(mut diagnostics: Diagnostics, genomes: Query<&Genome, With<Ant>>) {
+    let count = genomes.iter().len() as f64;
+    if count == 0.0 {
+        return;
+    }
+
+    let mut sums = [0.0; 4];
+    let mut squares = [0.0; 4];
+    for genome in genomes.iter() {
+        for (i, value) in genome.traits().into_iter().enumerate() {
+            sums[i] += value as f64;
+            squares[i] += (value as f64).powi(2);
+        }
+    }
+
+    for (i, (mean_path, std_path)) in Genome::DIAGNOSTICS.iter().enumerate() {
+        let mean = sums[i] / count;
+        let variance = (squares[i] / count - mean * mean).max(0.0);
+        diagnostics.add_measurement(mean_path, || mean);
+        diagnostics.add_measurement(std_path, || variance.sqrt());
+    }
+}
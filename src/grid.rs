@@ -1,43 +1,101 @@
+use std::collections::HashMap;
+
 use bevy::{prelude::*, window::PrimaryWindow};
-use rayon::prelude::*;
 
 use crate::{
-    camera::FocusedEntity, ui::UiControls, utils::window_to_world, ANT_VIEW_DISTANCE,
-    DEBUG_ANT_VIEW_RADIUS_COLOR, DEBUG_GRID_COLOR, GRID_AREA_SIZE, GRID_RESOLUTION,
+    camera::FocusedEntity,
+    terrain::Terrain,
+    ui::UiControls,
+    utils::{cursor_to_world, wrap_delta},
+    ANT_VIEW_DISTANCE, DEBUG_ANT_VIEW_RADIUS_COLOR, DEBUG_GRID_COLOR, GRID_AREA_SIZE,
+    GRID_RESOLUTION,
 };
 
-pub struct GridPlugin;
+/// The world grid, its topology can be chosen on the command line: `--topology torus`
+#[derive(Default)]
+pub struct GridPlugin {
+    pub topology: Topology,
+}
+
+impl GridPlugin {
+    /// Read the topology option, ignoring any other argument
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut args = args.into_iter();
+        let mut topology = Topology::default();
+        while let Some(arg) = args.next() {
+            if arg == "--topology" {
+                match args.next().as_deref().and_then(Topology::parse) {
+                    Some(parsed) => topology = parsed,
+                    None => warn!("--topology expects `bounded` or `torus`"),
+                }
+            }
+        }
+        Self { topology }
+    }
+}
 
 impl Plugin for GridPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Grid>()
+        app.insert_resource(Grid::default().with_topology(self.topology))
             .add_systems(Update, draw_grid)
             .add_systems(Update, update_grid_entities_grid)
-            .add_systems(Update, update_grid_entities_self_pos);
+            .add_systems(Update, update_grid_entities_self_pos)
+            .add_systems(Update, remove_despawned_grid_entities);
         // .add_system(update_grid.system());
     }
 }
 
+/// How the edges of the grid behave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Topology {
+    /// Entities are kept inside the grid and steer away from its borders
+    #[default]
+    Bounded,
+    /// Opposite edges are connected, positions and queries wrap around
+    Torus,
+}
+
+impl Topology {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "bounded" => Some(Topology::Bounded),
+            "torus" => Some(Topology::Torus),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GridEntityType {
     Ant,
     Food,
     Pheromone,
+    Predator,
 }
 
 #[derive(Component, Clone, Copy, Debug)]
 pub struct GridEntity {
     last_position: UVec2,
     current_position: UVec2,
+    entity_type: GridEntityType,
 }
 
 impl GridEntity {
-    pub fn new(position: UVec2) -> Self {
+    pub fn new(position: UVec2, entity_type: GridEntityType) -> Self {
         Self {
             last_position: position,
             current_position: position,
+            entity_type,
         }
     }
+
+    pub fn current_position(&self) -> UVec2 {
+        self.current_position
+    }
+
+    pub fn entity_type(&self) -> GridEntityType {
+        self.entity_type
+    }
 }
 
 impl Default for GridEntity {
@@ -45,6 +103,23 @@ impl Default for GridEntity {
         Self {
             last_position: UVec2::ZERO,
             current_position: UVec2::ZERO,
+            entity_type: GridEntityType::Ant,
+        }
+    }
+}
+
+/// Why an entity couldn't be moved in or out of a grid cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridError {
+    OutOfBounds(UVec2),
+    NotInCell(UVec2),
+}
+
+impl std::fmt::Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::OutOfBounds(pos) => write!(f, "cell {pos} is out of bounds"),
+            GridError::NotInCell(pos) => write!(f, "entity not found in cell {pos}"),
         }
     }
 }
@@ -57,7 +132,16 @@ pub struct Grid {
     pheromones: Vec<Vec<(GridEntity, Entity)>>,
     ants: Vec<Vec<(GridEntity, Entity)>>,
     food: Vec<Vec<(GridEntity, Entity)>>,
+    predators: Vec<Vec<(GridEntity, Entity)>>,
+    /// Layer and cell of every entity in the grid, to remove despawned ones
+    entity_cells: HashMap<Entity, (GridEntityType, UVec2)>,
+    walls: Vec<bool>, // Cells ants can't walk into
+    wall_count: usize,
+    terrain: Vec<Terrain>,
+    /// Bumped whenever walls or terrain change
+    layout_revision: u64,
     offset: Vec2, // Offset to align the grid with (0, 0) at the center
+    topology: Topology,
 }
 
 impl Default for Grid {
@@ -75,7 +159,14 @@ impl Default for Grid {
             ants: vec![vec![]; num_cells],
             food: vec![vec![]; num_cells],
             pheromones: vec![vec![]; num_cells],
+            predators: vec![vec![]; num_cells],
+            entity_cells: HashMap::new(),
+            walls: vec![false; num_cells],
+            wall_count: 0,
+            terrain: vec![Terrain::default(); num_cells],
+            layout_revision: 0,
             offset,
+            topology: Topology::default(),
         }
     }
 }
@@ -96,15 +187,67 @@ impl Grid {
             .outer_edges();
     }
 
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
     pub fn get_boundaries(&self) -> (Vec2, Vec2) {
         let min = self.offset;
         let max = self.offset + self.size.as_vec2() * self.cell_size;
         (min, max)
     }
 
+    /// World size of the grid, only when its edges wrap around
+    pub fn wrap_size(&self) -> Option<Vec2> {
+        match self.topology {
+            Topology::Bounded => None,
+            Topology::Torus => Some(self.size.as_vec2() * self.cell_size),
+        }
+    }
+
+    /// Bring a world position back into the grid, clamping or wrapping it
+    pub fn constrain(&self, world_pos: Vec2) -> Vec2 {
+        let (min, max) = self.get_boundaries();
+        match self.topology {
+            Topology::Bounded => world_pos.clamp(min, max),
+            Topology::Torus => min + (world_pos - min).rem_euclid(max - min),
+        }
+    }
+
+    /// Shortest vector going from `from` to `to`, crossing the edges on a torus
+    pub fn delta(&self, from: Vec2, to: Vec2) -> Vec2 {
+        match self.wrap_size() {
+            Some(size) => wrap_delta(to - from, size),
+            None => to - from,
+        }
+    }
+
+    pub fn distance(&self, from: Vec2, to: Vec2) -> f32 {
+        self.delta(from, to).length()
+    }
+
+    /// Map possibly out of range cell coordinates to a cell of the grid
+    fn wrap_cell(&self, x: i32, y: i32) -> Option<UVec2> {
+        match self.topology {
+            Topology::Bounded => {
+                (x >= 0 && y >= 0 && (x as u32) < self.size.x && (y as u32) < self.size.y)
+                    .then(|| UVec2::new(x as u32, y as u32))
+            }
+            Topology::Torus => Some(UVec2::new(
+                x.rem_euclid(self.size.x as i32) as u32,
+                y.rem_euclid(self.size.y as i32) as u32,
+            )),
+        }
+    }
+
     pub fn get_grid_pos(&self, world_pos: Vec2) -> UVec2 {
         // Adjust world position to grid-relative position
-        let relative_pos = world_pos - self.offset;
+        let relative_pos = self.constrain(world_pos) - self.offset;
 
         // Calculate grid indices
         let x = (relative_pos.x / self.cell_size.x).floor() as u32;
@@ -113,106 +256,188 @@ impl Grid {
         UVec2::new(x, y).clamp(UVec2::ZERO, self.size - 1)
     }
 
+    pub fn is_wall(&self, pos: UVec2) -> bool {
+        self.walls[(pos.x + pos.y * self.size.x) as usize]
+    }
+
+    /// Whether the cell under a world position blocks movement
+    pub fn is_wall_at(&self, world_pos: Vec2) -> bool {
+        self.is_wall(self.get_grid_pos(world_pos))
+    }
+
+    pub fn set_wall(&mut self, pos: UVec2, wall: bool) {
+        let index = (pos.x + pos.y * self.size.x) as usize;
+        if self.walls[index] != wall {
+            self.walls[index] = wall;
+            if wall {
+                self.wall_count += 1;
+            } else {
+                self.wall_count -= 1;
+            }
+            self.layout_revision += 1;
+        }
+    }
+
+    pub fn terrain(&self, pos: UVec2) -> Terrain {
+        self.terrain[(pos.x + pos.y * self.size.x) as usize]
+    }
+
+    /// Terrain of the cell under a world position
+    pub fn terrain_at(&self, world_pos: Vec2) -> Terrain {
+        self.terrain(self.get_grid_pos(world_pos))
+    }
+
+    pub fn set_terrain(&mut self, pos: UVec2, terrain: Terrain) {
+        let index = (pos.x + pos.y * self.size.x) as usize;
+        if self.terrain[index] != terrain {
+            self.terrain[index] = terrain;
+            self.layout_revision += 1;
+        }
+    }
+
+    /// Terrain of every cell, row by row from the bottom
+    pub fn terrain_layer(&self) -> &[Terrain] {
+        &self.terrain
+    }
+
+    /// Changes whenever a wall or the terrain of a cell changes
+    pub fn layout_revision(&self) -> u64 {
+        self.layout_revision
+    }
+
+    /// The four cells sharing an edge with a cell, missing past the borders of a bounded grid
+    pub fn neighbours(&self, pos: UVec2) -> impl Iterator<Item = UVec2> + '_ {
+        let (x, y) = (pos.x as i32, pos.y as i32);
+        [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+            .into_iter()
+            .filter_map(|(x, y)| self.wrap_cell(x, y))
+    }
+
+    /// Whether no wall stands on the straight line between two world positions
+    pub fn line_of_sight(&self, from: Vec2, to: Vec2) -> bool {
+        if self.wall_count == 0 {
+            return true;
+        }
+        let delta = self.delta(from, to);
+        // Sample every half cell along the line
+        let steps = (delta.length() / (self.cell_size.min_element() / 2.0)).ceil() as u32;
+        (1..steps).all(|step| !self.is_wall_at(from + delta * step as f32 / steps as f32))
+    }
+
+    /// Entities of a layer in the cells around a world position, at least `radius` around it
+    pub fn entities_near(
+        &self,
+        entity_type: GridEntityType,
+        world_pos: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = Entity> + '_ {
+        let layer = self.layer(entity_type);
+        self.get_cells_in_area_from_grid(self.get_grid_pos(world_pos), radius)
+            .into_iter()
+            .flat_map(move |pos| layer[(pos.x + pos.y * self.size.x) as usize].iter())
+            .map(|(_, entity)| *entity)
+    }
+
+    fn layer(&self, entity_type: GridEntityType) -> &Vec<Vec<(GridEntity, Entity)>> {
+        match entity_type {
+            GridEntityType::Ant => &self.ants,
+            GridEntityType::Food => &self.food,
+            GridEntityType::Pheromone => &self.pheromones,
+            GridEntityType::Predator => &self.predators,
+        }
+    }
+
+    fn layer_mut(&mut self, entity_type: GridEntityType) -> &mut Vec<Vec<(GridEntity, Entity)>> {
+        match entity_type {
+            GridEntityType::Ant => &mut self.ants,
+            GridEntityType::Food => &mut self.food,
+            GridEntityType::Pheromone => &mut self.pheromones,
+            GridEntityType::Predator => &mut self.predators,
+        }
+    }
+
     pub fn add_entity(
         &mut self,
         entity_type: GridEntityType,
         entity: (&GridEntity, Entity),
-    ) -> Result<(), ()> {
+    ) -> Result<(), GridError> {
         // Ensure indices are within bounds
         let curr_pos = entity.0.current_position;
         if curr_pos.x >= self.size.x || curr_pos.y >= self.size.y {
-            return Err(()); // Out of bounds
+            return Err(GridError::OutOfBounds(curr_pos));
         }
 
         // Compute the flattened index
         let index = ((curr_pos.x) + (curr_pos.y) * self.size.x) as usize;
 
         // Add the entity to the corresponding cell
-        match entity_type {
-            GridEntityType::Ant => self.ants[index].push((*entity.0, entity.1)),
-            GridEntityType::Food => self.food[index].push((*entity.0, entity.1)),
-            GridEntityType::Pheromone => self.pheromones[index].push((*entity.0, entity.1)),
-            #[allow(unreachable_patterns)]
-            _ => return Err(()),
-        }
+        self.layer_mut(entity_type)[index].push((*entity.0, entity.1));
+        self.entity_cells.insert(entity.1, (entity_type, curr_pos));
         Ok(())
     }
-    pub fn lazy_remove(
-        &mut self,
-        entity_type: GridEntityType,
-        entity: (&GridEntity, Entity),
-    ) -> Result<(), ()> {
-        let entity_id = entity.1;
-        let entity_type_vec = match entity_type {
-            GridEntityType::Ant => &mut self.ants,
-            GridEntityType::Food => &mut self.food,
-            GridEntityType::Pheromone => &mut self.pheromones,
-            #[allow(unreachable_patterns)]
-            _ => return Err(()),
+    /// Remove an entity from the cell it was last added to
+    pub fn remove(&mut self, entity: Entity) {
+        let Some((entity_type, pos)) = self.entity_cells.remove(&entity) else {
+            return;
         };
-
-        entity_type_vec.par_iter_mut().for_each(|entities| {
-            if let Some(index) = entities.iter().position(|e| e.1 == entity_id) {
-                entities.remove(index);
-            }
-        });
-        Ok(())
+        let index = ((pos.x) + (pos.y) * self.size.x) as usize;
+        self.layer_mut(entity_type)[index].retain(|e| e.1 != entity);
     }
     pub fn has_entity(
         &self,
         pos: UVec2,
         entity_type: GridEntityType,
         entity: (&GridEntity, Entity),
-    ) -> Result<bool, ()> {
+    ) -> bool {
         // Ensure indices are within bounds
         if pos.x >= self.size.x || pos.y >= self.size.y {
-            return Ok(false); // Out of bounds
+            return false; // Out of bounds
         }
 
         // Compute the flattened index
         let index = ((pos.x) + (pos.y) * self.size.x) as usize;
 
         // Check if the entity is in the corresponding cell
-        match entity_type {
-            GridEntityType::Ant => Ok(self.ants[index].iter().any(|e| e.1 == entity.1)),
-            GridEntityType::Food => Ok(self.food[index].iter().any(|e| e.1 == entity.1)),
-            GridEntityType::Pheromone => Ok(self.pheromones[index].iter().any(|e| e.1 == entity.1)),
-            #[allow(unreachable_patterns)]
-            _ => return Err(()),
-        }
+        self.layer(entity_type)[index]
+            .iter()
+            .any(|e| e.1 == entity.1)
     }
     pub fn remove_from(
         &mut self,
         entity_type: GridEntityType,
         pos: UVec2,
         entity: (&GridEntity, Entity),
-    ) -> Result<(), ()> {
+    ) -> Result<(), GridError> {
         // Remove the entity from the corresponding cell
-        match self.has_entity(pos, entity_type, entity) {
-            Ok(true) => {
-                let index = ((pos.x) + (pos.y) * self.size.x) as usize;
-                match entity_type {
-                    GridEntityType::Ant => Ok(self.ants[index].retain(|e| e.1 != entity.1)),
-                    GridEntityType::Food => Ok(self.food[index].retain(|e| e.1 != entity.1)),
-                    GridEntityType::Pheromone => {
-                        Ok(self.pheromones[index].retain(|e| e.1 != entity.1))
-                    }
-                    #[allow(unreachable_patterns)]
-                    _ => return Err(()),
-                }
-            }
-            #[allow(unreachable_patterns)]
-            _ => return Err(()),
+        if !self.has_entity(pos, entity_type, entity) {
+            return Err(GridError::NotInCell(pos));
         }
+        let index = ((pos.x) + (pos.y) * self.size.x) as usize;
+        self.layer_mut(entity_type)[index].retain(|e| e.1 != entity.1);
+        self.entity_cells.remove(&entity.1);
+        Ok(())
+    }
+
+    pub fn get_size(&self) -> UVec2 {
+        self.size
+    }
+
+    pub fn get_cell_size(&self) -> Vec2 {
+        self.cell_size
+    }
+
+    /// World position of the center of a cell
+    pub fn get_world_pos(&self, pos: UVec2) -> Vec2 {
+        self.offset
+            + Vec2::new(pos.x as f32, pos.y as f32) * self.cell_size
+            + (self.cell_size * 0.5)
     }
 
     pub fn draw_cell<C>(&self, gizmos: &mut Gizmos, pos: UVec2, color: C)
     where
         C: Into<Color> + Copy,
     {
-        let cell_position = self.offset
-            + Vec2::new(pos.x as f32, pos.y as f32) * self.cell_size
-            + (self.cell_size * 0.5);
+        let cell_position = self.get_world_pos(pos);
         let color_converted = color.into();
         gizmos.circle_2d(
             Isometry2d {
@@ -233,13 +458,24 @@ impl Grid {
     }
 
     pub fn get_cells_in_area_from_grid(&self, grid_pos: UVec2, radius: f32) -> Vec<UVec2> {
-        let radius = (radius / GRID_RESOLUTION).ceil() as u32;
+        let radius = (radius / GRID_RESOLUTION).ceil() as i32;
+        let center = grid_pos.as_ivec2();
+        let (min, max) = match self.topology {
+            Topology::Bounded => (
+                (center - radius).max(IVec2::ZERO),
+                (center + radius).min(self.size.as_ivec2() - 1),
+            ),
+            // Never visit the same cell twice when the area wraps around the whole grid
+            Topology::Torus => (
+                center - radius,
+                (center + radius).min(center - radius + self.size.as_ivec2() - 1),
+            ),
+        };
         let mut cells = Vec::new();
 
-        for y in (grid_pos.y.saturating_sub(radius))..=(grid_pos.y.saturating_add(radius)) {
-            for x in (grid_pos.x.saturating_sub(radius))..=(grid_pos.x.saturating_add(radius)) {
-                let pos = UVec2::new(x, y);
-                if pos.x < self.size.x && pos.y < self.size.y {
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                if let Some(pos) = self.wrap_cell(x, y) {
                     cells.push(pos);
                 }
             }
@@ -264,37 +500,43 @@ impl Grid {
         let max_x = ((world_pos.x + radius - self.offset.x) / self.cell_size.x).ceil() as i32;
         let min_y = ((world_pos.y - radius - self.offset.y) / self.cell_size.y).floor() as i32;
         let max_y = ((world_pos.y + radius - self.offset.y) / self.cell_size.y).ceil() as i32;
+        // Never visit the same cell twice when the area wraps around the whole grid
+        let (max_x, max_y) = match self.topology {
+            Topology::Bounded => (max_x, max_y),
+            Topology::Torus => (
+                max_x.min(min_x + self.size.x as i32 - 1),
+                max_y.min(min_y + self.size.y as i32 - 1),
+            ),
+        };
 
         for y in min_y..=max_y {
             for x in min_x..=max_x {
-                if x >= 0 && y >= 0 {
-                    let pos = UVec2::new(x as u32, y as u32);
-                    if pos.x < self.size.x && pos.y < self.size.y {
-                        let cell_min =
-                            self.offset + Vec2::new(pos.x as f32, pos.y as f32) * self.cell_size;
-                        let cell_max = cell_min + self.cell_size;
-
-                        let corners = [
-                            cell_min,
-                            Vec2::new(cell_min.x, cell_max.y),
-                            Vec2::new(cell_max.x, cell_min.y),
-                            cell_max,
-                        ];
-
-                        if corners
-                            .iter()
-                            .any(|&corner| corner.distance(world_pos) <= radius)
-                        {
-                            cells.push((
-                                pos,
-                                (
-                                    self.ants[((pos.x) + (pos.y) * self.size.x) as usize].clone(),
-                                    self.food[((pos.x) + (pos.y) * self.size.x) as usize].clone(),
-                                    self.pheromones[((pos.x) + (pos.y) * self.size.x) as usize]
-                                        .clone(),
-                                ),
-                            ));
-                        }
+                if let Some(pos) = self.wrap_cell(x, y) {
+                    // Distances to the corners go the short way round on a torus, so
+                    // cells found across the edges are checked against their closest copy
+                    let cell_min = self.offset + Vec2::new(x as f32, y as f32) * self.cell_size;
+                    let cell_max = cell_min + self.cell_size;
+
+                    let corners = [
+                        cell_min,
+                        Vec2::new(cell_min.x, cell_max.y),
+                        Vec2::new(cell_max.x, cell_min.y),
+                        cell_max,
+                    ];
+
+                    if corners
+                        .iter()
+                        .any(|&corner| self.distance(world_pos, corner) <= radius)
+                    {
+                        let index = (pos.x + pos.y * self.size.x) as usize;
+                        cells.push((
+                            pos,
+                            (
+                                self.ants[index].clone(),
+                                self.food[index].clone(),
+                                self.pheromones[index].clone(),
+                            ),
+                        ));
                     }
                 }
             }
@@ -308,22 +550,21 @@ fn draw_grid(
     mut gizmos: Gizmos,
     mut last_cursor_pos: Local<UVec2>,
     windows: Query<&Window, With<PrimaryWindow>>,
-    camera_query: Query<&Transform, With<Camera2d>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
     entity_query: Query<(&Transform, Entity), With<GridEntity>>,
     focused_entity: Res<FocusedEntity>,
-    ui_controlss: Res<UiControls>,
+    ui_controls: Res<UiControls>,
 ) {
-    if ui_controlss.show_grid == false {
+    if !ui_controls.show_grid {
         return;
     }
     let window = windows.single();
-    let camera_transform = camera_query.single(); // Immutable access to the camera transform
+    let (camera, camera_transform) = camera_query.single();
 
     grid.draw_grid(&mut gizmos);
 
-    if let Some(cursor_position) = window.cursor_position() {
-        let grid_pos =
-            grid.get_grid_pos(window_to_world(cursor_position, window, camera_transform));
+    if let Some(cursor_world_pos) = cursor_to_world(window, camera, camera_transform) {
+        let grid_pos = grid.get_grid_pos(cursor_world_pos);
         if *last_cursor_pos != grid_pos {
             last_cursor_pos.clone_from(&grid_pos);
         }
@@ -362,37 +603,35 @@ fn update_grid_entities_self_pos(
     }
 }
 
-fn update_grid_entities_grid(mut grid: ResMut<Grid>, entities: Query<(&GridEntity, Entity)>) {
-    for g_entity in entities.iter() {
+fn update_grid_entities_grid(mut grid: ResMut<Grid>, entities: Query<(Ref<GridEntity>, Entity)>) {
+    for (grid_entity, entity) in entities.iter() {
+        let g_entity = (&*grid_entity, entity);
+        let entity_type = g_entity.0.entity_type;
         // check if the entity is in the grid
-        match grid.has_entity(g_entity.0.current_position, GridEntityType::Ant, g_entity) {
-            Ok(false) => {
-                match grid.has_entity(g_entity.0.last_position, GridEntityType::Ant, g_entity) {
-                    Ok(false) => {
-                        println!("Entity not in grid {:?}", g_entity.0);
-                    }
-                    Ok(true) => {
-                        if let Err(_) = grid.remove_from(
-                            GridEntityType::Ant,
-                            g_entity.0.last_position,
-                            g_entity,
-                        ) {
-                            println!("Error removing entity from grid");
-                        }
-                    }
-                    Err(_) => {
-                        println!("Error checking entity in grid");
-                    }
-                }
-
-                if let Err(_) = grid.add_entity(GridEntityType::Ant, g_entity) {
-                    println!("grid position out of bounds entity {:?}", g_entity);
+        if !grid.has_entity(g_entity.0.current_position, entity_type, g_entity) {
+            if grid.has_entity(g_entity.0.last_position, entity_type, g_entity) {
+                if let Err(error) =
+                    grid.remove_from(entity_type, g_entity.0.last_position, g_entity)
+                {
+                    println!("Error removing entity from grid: {error}");
                 }
+            } else if !grid_entity.is_added() {
+                // Entities spawned since the last update are expected to be missing
+                println!("Entity not in grid {:?}", g_entity.0);
             }
-            Ok(true) => {}
-            Err(_) => {
-                println!("Error checking entity in grid");
+
+            if let Err(error) = grid.add_entity(entity_type, g_entity) {
+                println!("Error adding entity {:?} to grid: {error}", g_entity);
             }
         }
     }
 }
+
+fn remove_despawned_grid_entities(
+    mut grid: ResMut<Grid>,
+    mut removed: RemovedComponents<GridEntity>,
+) {
+    for entity in removed.read() {
+        grid.remove(entity);
+    }
+}
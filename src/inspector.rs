@@ -0,0 +1,146 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+use crate::{
+    ant::{Ant, AntPerception},
+    camera::FocusedEntity,
+    caste::Caste,
+    colony::ColonyId,
+    grid::Grid,
+    pheromone::{PheromoneMap, Pheromones},
+};
+
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup)
+            .add_systems(Update, update_inspector);
+    }
+}
+
+/// Panel describing the focused ant
+#[derive(Component)]
+struct Inspector;
+
+#[derive(Component)]
+struct InspectorText;
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                bottom: Val::Px(0.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.75)),
+            GlobalZIndex(i32::MAX),
+            Visibility::Hidden,
+            Interaction::default(),
+            Inspector,
+        ))
+        .with_child((
+            Text::default(),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            InspectorText,
+        ));
+}
+
+fn update_inspector(
+    focused_entity: Res<FocusedEntity>,
+    ants: Query<(&Transform, &Ant, &Caste, &ColonyId, &AntPerception)>,
+    grid: Res<Grid>,
+    pheromone_map: Option<Res<PheromoneMap>>,
+    mut panel: Single<&mut Visibility, With<Inspector>>,
+    mut text: Single<&mut Text, With<InspectorText>>,
+) {
+    let Some((entity, (transform, ant, caste, colony, perception))) = focused_entity
+        .0
+        .and_then(|entity| ants.get(entity).ok().map(|ant| (entity, ant)))
+    else {
+        **panel = Visibility::Hidden;
+        return;
+    };
+    **panel = Visibility::Inherited;
+
+    let position = transform.translation.truncate();
+    let heading = (transform.rotation * Vec3::Y).truncate();
+    let cell = grid.get_grid_pos(position);
+
+    let mut info = String::new();
+    let _ = writeln!(
+        info,
+        "Ant {entity} ({}, colony {})",
+        caste.name(),
+        colony.0 + 1
+    );
+    let _ = writeln!(info, "Position: ({:.0}, {:.0})", position.x, position.y);
+    let _ = writeln!(
+        info,
+        "Heading: {:.0}° ({:.2}, {:.2})",
+        heading.to_angle().to_degrees(),
+        heading.x,
+        heading.y
+    );
+    let desired_direction = ant.desired_direction();
+    let _ = writeln!(
+        info,
+        "Desired direction: ({:.2}, {:.2})",
+        desired_direction.x, desired_direction.y
+    );
+    let _ = writeln!(info, "Target: {:?}", ant.desired_target());
+    if let Some(food) = ant.known_food() {
+        let _ = writeln!(info, "Known food: ({:.0}, {:.0})", food.x, food.y);
+    }
+    let _ = writeln!(info, "Carrying: {:.2}", ant.carrying());
+    let _ = writeln!(info, "Energy: {:.0}", ant.energy());
+    let _ = writeln!(
+        info,
+        "Cell: ({}, {}), {:?}",
+        cell.x,
+        cell.y,
+        grid.terrain(cell)
+    );
+    let _ = writeln!(
+        info,
+        "Scent: food {:.2}, trail {:.2}",
+        perception.food_scent.length(),
+        perception.pheromone_scent.length()
+    );
+    let _ = write!(info, "Visible ants: {}", perception.visible_ants.len());
+    for seen in perception.visible_ants.iter().take(5) {
+        let _ = write!(info, " {seen}");
+    }
+    if perception.visible_ants.len() > 5 {
+        let _ = write!(info, " ...");
+    }
+    let _ = writeln!(info);
+
+    // Pheromone levels of its own colony under the left, ahead and right sensors
+    if let Some(pheromone_map) = pheromone_map {
+        let sensors = ant.sensor_positions(transform);
+        for pheromone in Pheromones::ALL {
+            let [left, ahead, right] =
+                sensors.map(|sensor| pheromone_map.sample(&grid, *colony, pheromone, sensor));
+            let _ = writeln!(
+                info,
+                "{pheromone:?}: {left:.2} / {ahead:.2} / {right:.2}{}",
+                if pheromone == ant.followed_pheromone() {
+                    " (following)"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+
+    text.0 = info.trim_end().to_string();
+}
@@ -1,7 +1,26 @@
+// Bevy systems routinely take many parameters and complex query types
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
 pub mod ant;
+pub mod bindings;
 pub mod camera;
+pub mod capture;
+pub mod caste;
+pub mod charts;
+pub mod colony;
 pub mod constants;
+pub mod food;
+pub mod genome;
 pub mod grid;
+pub mod inspector;
+pub mod minimap;
+pub mod pheromone;
+pub mod predator;
+pub mod rng;
+pub mod selection;
+pub mod terrain;
+pub mod tools;
+pub mod trail;
 pub mod ui;
 pub mod utils;
 
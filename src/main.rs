@@ -1,4 +1,11 @@
-use ant_behaviour::{ant::AntPlugin, camera::CameraPlugin, grid::GridPlugin, ui::UiPlugin};
+use ant_behaviour::{
+    ant::AntPlugin, bindings::BindingsPlugin, camera::CameraPlugin, capture::CapturePlugin,
+    charts::ChartsPlugin, colony::ColonyPlugin, food::FoodPlugin, genome::GenomePlugin,
+    grid::GridPlugin, inspector::InspectorPlugin, minimap::MinimapPlugin,
+    pheromone::PheromonePlugin, predator::PredatorPlugin, rng::RngPlugin,
+    selection::SelectionPlugin, terrain::TerrainPlugin, tools::ToolsPlugin, trail::TrailPlugin,
+    ui::UiPlugin,
+};
 use bevy::prelude::*;
 
 fn main() {
@@ -16,7 +23,30 @@ fn main() {
                     ..default()
                 }),
         )
-        .add_plugins((CameraPlugin, UiPlugin))
-        .add_plugins((GridPlugin, AntPlugin))
+        .add_plugins((
+            BindingsPlugin,
+            CameraPlugin,
+            UiPlugin,
+            InspectorPlugin,
+            MinimapPlugin,
+            ChartsPlugin,
+            CapturePlugin::from_args(std::env::args().skip(1)),
+        ))
+        .add_plugins((
+            RngPlugin::from_args(std::env::args().skip(1)),
+            GridPlugin::from_args(std::env::args().skip(1)),
+            TerrainPlugin::from_args(std::env::args().skip(1)),
+            ColonyPlugin::from_args(std::env::args().skip(1)),
+            PheromonePlugin,
+        ))
+        .add_plugins((
+            FoodPlugin,
+            GenomePlugin,
+            AntPlugin,
+            ToolsPlugin,
+            SelectionPlugin,
+            TrailPlugin,
+            PredatorPlugin,
+        ))
         .run();
 }
@@ -0,0 +1,183 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    ui::RelativeCursorPosition,
+    window::PrimaryWindow,
+};
+
+use crate::{
+    ant::{Ant, AntSettings},
+    camera::{CameraFollow, CameraTarget, FocusedEntity},
+    colony::{Colonies, ColonyId},
+    food::FoodSource,
+    grid::Grid,
+    utils::window_to_world,
+    FOOD_COLOR, MINIMAP_SIZE,
+};
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup)
+            .add_systems(Update, (minimap_click, draw_minimap, update_viewport_rect));
+    }
+}
+
+/// Overview of the whole world, one pixel per grid cell
+#[derive(Component)]
+struct Minimap;
+
+/// Outline of the area seen by the camera
+#[derive(Component)]
+struct MinimapViewport;
+
+/// Height of the minimap on screen, it keeps the aspect ratio of the grid
+pub fn minimap_height(grid: &Grid) -> f32 {
+    let size = grid.get_size();
+    MINIMAP_SIZE * size.y as f32 / size.x as f32
+}
+
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>, grid: Res<Grid>) {
+    let size = grid.get_size();
+    let image = Image::new_fill(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(0.0),
+                bottom: Val::Px(0.0),
+                width: Val::Px(MINIMAP_SIZE),
+                height: Val::Px(minimap_height(&grid)),
+                border: UiRect::all(Val::Px(2.0)),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            BorderColor(Color::srgb(0.5, 0.5, 0.5)),
+            ImageNode::new(images.add(image)),
+            GlobalZIndex(i32::MAX),
+            Interaction::default(),
+            RelativeCursorPosition::default(),
+            Minimap,
+        ))
+        .with_child((
+            Node {
+                position_type: PositionType::Absolute,
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BorderColor(Color::WHITE),
+            MinimapViewport,
+        ));
+}
+
+/// Clicking or dragging on the minimap sends the camera there
+fn minimap_click(
+    minimap: Single<(&Interaction, &RelativeCursorPosition), With<Minimap>>,
+    camera: Single<&Transform, With<Camera2d>>,
+    grid: Res<Grid>,
+    mut camera_target: ResMut<CameraTarget>,
+    mut focused_entity: ResMut<FocusedEntity>,
+    mut follow: ResMut<CameraFollow>,
+) {
+    let (interaction, cursor) = *minimap;
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+    let Some(position) = cursor.normalized else {
+        return;
+    };
+    let (min, max) = grid.get_boundaries();
+    let position = position.clamp(Vec2::ZERO, Vec2::ONE);
+    // UI y goes down, world y goes up
+    let world_pos = min + Vec2::new(position.x, 1.0 - position.y) * (max - min);
+    focused_entity.0 = None;
+    *follow = CameraFollow::Free;
+    camera_target.0 = Some(world_pos.extend(camera.scale.x));
+}
+
+fn draw_minimap(
+    minimap: Single<&ImageNode, With<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
+    grid: Res<Grid>,
+    colonies: Res<Colonies>,
+    ants_settings: Res<AntSettings>,
+    ants: Query<(&Transform, &ColonyId), With<Ant>>,
+    food_sources: Query<&Transform, With<FoodSource>>,
+) {
+    let Some(image) = images.get_mut(&minimap.image) else {
+        return;
+    };
+    let size = grid.get_size();
+    let mut pixels = vec![Vec3::splat(0.05); (size.x * size.y) as usize];
+    // Image rows go down while grid rows go up
+    let pixel = |cell: UVec2| (cell.x + (size.y - 1 - cell.y) * size.x) as usize;
+
+    // Nests as discs of their colony colour
+    for (_, colony) in colonies.iter() {
+        let [r, g, b, _] = colony.color;
+        for cell in grid.get_cells_in_area_from_grid(
+            grid.get_grid_pos(colony.nest_position),
+            ants_settings.nest_size,
+        ) {
+            if grid.distance(grid.get_world_pos(cell), colony.nest_position)
+                <= ants_settings.nest_size
+            {
+                pixels[pixel(cell)] = Vec3::new(r, g, b) * 0.5;
+            }
+        }
+    }
+
+    for transform in food_sources.iter() {
+        let [r, g, b, _] = FOOD_COLOR;
+        pixels[pixel(grid.get_grid_pos(transform.translation.truncate()))] = Vec3::new(r, g, b);
+    }
+
+    // Ant density, every ant brightens its cell with its colony colour
+    for (transform, colony) in ants.iter() {
+        let [r, g, b, _] = colonies.get(*colony).color;
+        let index = pixel(grid.get_grid_pos(transform.translation.truncate()));
+        pixels[index] = (pixels[index] + Vec3::new(r, g, b) * 0.35).min(Vec3::ONE);
+    }
+
+    for (i, color) in pixels.into_iter().enumerate() {
+        let color = Color::linear_rgb(color.x, color.y, color.z).to_srgba();
+        image.data[i * 4..i * 4 + 4].copy_from_slice(&color.to_u8_array());
+    }
+}
+
+fn update_viewport_rect(
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    grid: Res<Grid>,
+    mut viewport: Single<&mut Node, With<MinimapViewport>>,
+) {
+    let (camera, camera_transform) = *camera;
+    let (Some(top_left), Some(bottom_right)) = (
+        window_to_world(Vec2::ZERO, camera, camera_transform),
+        window_to_world(window.size(), camera, camera_transform),
+    ) else {
+        return;
+    };
+    let (min, max) = grid.get_boundaries();
+    let world_size = max - min;
+
+    viewport.left = Val::Percent((top_left.x - min.x) / world_size.x * 100.0);
+    viewport.top = Val::Percent((max.y - top_left.y) / world_size.y * 100.0);
+    viewport.width = Val::Percent((bottom_right.x - top_left.x) / world_size.x * 100.0);
+    viewport.height = Val::Percent((top_left.y - bottom_right.y) / world_size.y * 100.0);
+}
@@ -0,0 +1,311 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use rayon::prelude::*;
+
+use crate::{
+    colony::{Colonies, ColonyId},
+    grid::Grid,
+    terrain::Terrain,
+    ui::UiControls,
+    PHEROMONE_ALARM_DECAY, PHEROMONE_DECAY, PHEROMONE_DEPOSIT, PHEROMONE_DIFFUSION,
+    PHEROMONE_DRAW_THRESHOLD, PHEROMONE_HEATMAP_INTENSITY, PHEROMONE_HEATMAP_OPACITY,
+    PHEROMONE_MAX,
+};
+
+pub struct PheromonePlugin;
+
+impl Plugin for PheromonePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PheromoneSettings>()
+            .add_systems(Startup, (setup, setup_heatmap))
+            .add_systems(Update, (decay_pheromones, diffuse_pheromones).chain())
+            .add_systems(Update, update_heatmap.after(diffuse_pheromones));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pheromones {
+    /// Left by ants searching for food, it leads back home
+    LookingForFood,
+    /// Left by ants carrying food home, it leads to the food
+    LookingForHome,
+    /// Left by ants that met a predator, it fades quickly
+    Alarm,
+}
+
+impl Pheromones {
+    pub const ALL: [Pheromones; 3] = [
+        Pheromones::LookingForFood,
+        Pheromones::LookingForHome,
+        Pheromones::Alarm,
+    ];
+
+    /// Remaining pheromone after `delta_secs`, `decay` is the share kept each second
+    pub fn decay(&self, pheromone: f32, settings: &PheromoneSettings, delta_secs: f32) -> f32 {
+        match self {
+            Pheromones::Alarm => pheromone * PHEROMONE_ALARM_DECAY.powf(delta_secs),
+            _ => pheromone * settings.decay.powf(delta_secs),
+        }
+    }
+
+    pub fn get_color(&self) -> [f32; 4] {
+        match self {
+            Pheromones::LookingForFood => [1.0, 0.0, 0.0, 1.0],
+            Pheromones::LookingForHome => [0.0, 0.0, 1.0, 1.0],
+            Pheromones::Alarm => [1.0, 0.6, 0.0, 1.0],
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Pheromones::LookingForFood => 0,
+            Pheromones::LookingForHome => 1,
+            Pheromones::Alarm => 2,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct PheromoneSettings {
+    /// Share of a trail kept each second on grass
+    pub decay: f32,
+    /// Share of a cell spreading to its neighbours each second on grass
+    pub diffusion: f32,
+    pub max: f32,
+    /// Amount an ant lays each second
+    pub deposit: f32,
+    /// Scales the levels shown on the heatmap
+    pub heatmap_intensity: f32,
+    /// Opacity of the strongest cells of the heatmap
+    pub heatmap_opacity: f32,
+}
+
+impl Default for PheromoneSettings {
+    fn default() -> Self {
+        Self {
+            decay: PHEROMONE_DECAY,
+            diffusion: PHEROMONE_DIFFUSION,
+            max: PHEROMONE_MAX,
+            deposit: PHEROMONE_DEPOSIT,
+            heatmap_intensity: PHEROMONE_HEATMAP_INTENSITY,
+            heatmap_opacity: PHEROMONE_HEATMAP_OPACITY,
+        }
+    }
+}
+
+/// Pheromone level of every grid cell, one layer per colony and pheromone type
+#[derive(Resource, Debug, Clone)]
+pub struct PheromoneMap {
+    size: UVec2,
+    layers: Vec<Vec<f32>>,
+}
+
+impl PheromoneMap {
+    pub fn new(size: UVec2, n_colonies: usize) -> Self {
+        let num_cells = (size.x * size.y) as usize;
+        Self {
+            size,
+            layers: vec![vec![0.0; num_cells]; n_colonies * Pheromones::ALL.len()],
+        }
+    }
+
+    fn layer_index(&self, colony: ColonyId, pheromone: Pheromones) -> usize {
+        colony.0 * Pheromones::ALL.len() + pheromone.index()
+    }
+
+    fn cell_index(&self, pos: UVec2) -> usize {
+        (pos.x + pos.y * self.size.x) as usize
+    }
+
+    pub fn get(&self, colony: ColonyId, pheromone: Pheromones, pos: UVec2) -> f32 {
+        self.layers[self.layer_index(colony, pheromone)][self.cell_index(pos)]
+    }
+
+    /// Pheromone level in the cell under a world position
+    pub fn sample(
+        &self,
+        grid: &Grid,
+        colony: ColonyId,
+        pheromone: Pheromones,
+        world_pos: Vec2,
+    ) -> f32 {
+        self.get(colony, pheromone, grid.get_grid_pos(world_pos))
+    }
+
+    pub fn deposit(
+        &mut self,
+        colony: ColonyId,
+        pheromone: Pheromones,
+        pos: UVec2,
+        amount: f32,
+        settings: &PheromoneSettings,
+    ) {
+        let layer = self.layer_index(colony, pheromone);
+        let cell = self.cell_index(pos);
+        self.layers[layer][cell] = (self.layers[layer][cell] + amount).min(settings.max);
+    }
+
+    /// Remove up to `amount` pheromone from a cell
+    pub fn erase(&mut self, colony: ColonyId, pheromone: Pheromones, pos: UVec2, amount: f32) {
+        let layer = self.layer_index(colony, pheromone);
+        let cell = self.cell_index(pos);
+        self.layers[layer][cell] = (self.layers[layer][cell] - amount).max(0.0);
+    }
+}
+
+/// World-space sprite showing the pheromone map
+#[derive(Component)]
+struct PheromoneHeatmap;
+
+fn setup(mut commands: Commands, grid: Res<Grid>, colonies: Res<Colonies>) {
+    commands.insert_resource(PheromoneMap::new(grid.get_size(), colonies.len()));
+}
+
+/// Evaporate the pheromones, faster or slower depending on the terrain of each cell
+fn decay_pheromones(
+    mut pheromone_map: ResMut<PheromoneMap>,
+    settings: Res<PheromoneSettings>,
+    grid: Res<Grid>,
+    time: Res<Time>,
+) {
+    let delta_secs = time.delta_secs();
+    for (i, layer) in pheromone_map.layers.iter_mut().enumerate() {
+        let pheromone = Pheromones::ALL[i % Pheromones::ALL.len()];
+        // Share kept on each terrain
+        let kept = Terrain::ALL
+            .map(|terrain| pheromone.decay(1.0, &settings, delta_secs * terrain.evaporation()));
+        layer
+            .iter_mut()
+            .zip(grid.terrain_layer())
+            .filter(|(level, _)| **level > 0.0)
+            .for_each(|(level, terrain)| *level *= kept[*terrain as usize]);
+    }
+}
+
+/// Spread part of each cell to its neighbours that aren't walls, the total is kept
+fn diffuse_pheromones(
+    mut pheromone_map: ResMut<PheromoneMap>,
+    settings: Res<PheromoneSettings>,
+    grid: Res<Grid>,
+    time: Res<Time>,
+) {
+    let rate = settings.diffusion * time.delta_secs();
+    if rate <= 0.0 {
+        return;
+    }
+    let shares = Terrain::ALL.map(|terrain| (rate * terrain.diffusion()).min(1.0));
+    let size = pheromone_map.size;
+    let index = |pos: UVec2| (pos.x + pos.y * size.x) as usize;
+
+    pheromone_map.layers.par_iter_mut().for_each(|layer| {
+        let mut spread = layer.clone();
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let pos = UVec2::new(x, y);
+                let level = layer[index(pos)];
+                if level <= 0.0 {
+                    continue;
+                }
+                let open = |neighbour: &UVec2| !grid.is_wall(*neighbour);
+                let count = grid.neighbours(pos).filter(open).count();
+                if count == 0 {
+                    continue;
+                }
+                let share = level * shares[grid.terrain(pos) as usize];
+                spread[index(pos)] -= share;
+                for neighbour in grid.neighbours(pos).filter(open) {
+                    spread[index(neighbour)] += share / count as f32;
+                }
+            }
+        }
+        for (level, spread) in layer.iter_mut().zip(spread) {
+            *level = spread.min(settings.max);
+        }
+    });
+}
+
+fn setup_heatmap(mut commands: Commands, mut images: ResMut<Assets<Image>>, grid: Res<Grid>) {
+    let size = grid.get_size();
+    let image = Image::new_fill(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::default(),
+    );
+    let (min, max) = grid.get_boundaries();
+    commands.spawn((
+        Sprite {
+            image: images.add(image),
+            custom_size: Some(max - min),
+            ..default()
+        },
+        // Below the food and the ants
+        Transform::from_translation(((min + max) / 2.0).extend(-1.0)),
+        Visibility::Hidden,
+        PheromoneHeatmap,
+    ));
+}
+
+/// Paint the pheromone levels of every colony into the heatmap texture
+fn update_heatmap(
+    pheromone_map: Res<PheromoneMap>,
+    settings: Res<PheromoneSettings>,
+    grid: Res<Grid>,
+    colonies: Res<Colonies>,
+    ui_controls: Res<UiControls>,
+    mut heatmap: Single<(&Sprite, &mut Visibility), With<PheromoneHeatmap>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let (sprite, visibility) = &mut *heatmap;
+    if !ui_controls.show_pheromones {
+        **visibility = Visibility::Hidden;
+        return;
+    }
+    **visibility = Visibility::Inherited;
+    let Some(image) = images.get_mut(&sprite.image) else {
+        return;
+    };
+
+    let size = grid.get_size();
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let pos = UVec2::new(x, y);
+            let mut color = Vec3::ZERO;
+            let mut strongest: f32 = 0.0;
+            for pheromone in Pheromones::ALL {
+                let level = colonies
+                    .iter()
+                    .map(|(colony, _)| pheromone_map.get(colony, pheromone, pos))
+                    .sum::<f32>()
+                    / settings.max;
+                if level < PHEROMONE_DRAW_THRESHOLD {
+                    continue;
+                }
+                let level = (level * settings.heatmap_intensity).min(1.0);
+                let [r, g, b, _] = pheromone.get_color();
+                color += Vec3::new(r, g, b) * level;
+                strongest = strongest.max(level);
+            }
+            let color = color.min(Vec3::ONE);
+            let alpha = strongest * settings.heatmap_opacity;
+            // Image rows go down while grid rows go up
+            let pixel = ((x + (size.y - 1 - y) * size.x) * 4) as usize;
+            image.data[pixel..pixel + 4].copy_from_slice(&[
+                (color.x * 255.0) as u8,
+                (color.y * 255.0) as u8,
+                (color.z * 255.0) as u8,
+                (alpha * 255.0) as u8,
+            ]);
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    ant::{ant_fight, Ant},
+    camera::FocusableEntity,
+    caste::{Caste, CasteSettings},
+    colony::{Colonies, ColonyId},
+    grid::{Grid, GridEntity, GridEntityType, Topology},
+    pheromone::{PheromoneMap, PheromoneSettings, Pheromones},
+    rng::SimulationRng,
+    ui::UiControls,
+    utils::ViewCone,
+    ANT_FIGHT_DAMAGE, ANT_SIZE, PREDATOR_CHASE_SPEED, PREDATOR_COLOR, PREDATOR_COUNT,
+    PREDATOR_EAT_TIME, PREDATOR_HEALTH, PREDATOR_RESPAWN_INTERVAL, PREDATOR_ROTATION_SPEED,
+    PREDATOR_SIZE, PREDATOR_SPEED, PREDATOR_VIEW_ANGLE, PREDATOR_VIEW_DISTANCE,
+};
+
+pub struct PredatorPlugin;
+
+impl Plugin for PredatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_predators)
+            .add_systems(
+                Update,
+                (
+                    respawn_predators,
+                    predator_hunt,
+                    move_predators,
+                    predator_attack.after(ant_fight),
+                )
+                    .chain(),
+            )
+            .add_systems(Update, draw_predators);
+    }
+}
+
+/// Hunts ants on its own, soldiers can wear it down
+#[derive(Component, Debug)]
+pub struct Predator {
+    desired_direction: Vec2,
+    /// Ant being chased
+    prey: Option<Entity>,
+    health: f32,
+    /// Seconds left on the last catch, it doesn't hunt meanwhile
+    eating: f32,
+}
+
+impl Default for Predator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Predator {
+    pub fn new() -> Self {
+        Self {
+            desired_direction: Vec2::ZERO,
+            prey: None,
+            health: PREDATOR_HEALTH,
+            eating: 0.0,
+        }
+    }
+
+    pub fn prey(&self) -> Option<Entity> {
+        self.prey
+    }
+
+    pub fn health(&self) -> f32 {
+        self.health
+    }
+
+    pub fn get_view_cone(&self, transform: &Transform, grid: &Grid) -> ViewCone {
+        let mut view_cone = ViewCone::new(
+            transform.translation.truncate(),
+            PREDATOR_VIEW_DISTANCE,
+            PREDATOR_VIEW_ANGLE,
+            transform.rotation.to_euler(EulerRot::XYZ).2,
+        );
+        view_cone.wrap_around(grid.wrap_size());
+        view_cone
+    }
+}
+
+/// Random position away from the nests, off the walls
+fn random_predator_position(
+    grid: &Grid,
+    colonies: &Colonies,
+    rng: &mut SimulationRng,
+) -> Option<Vec2> {
+    let (min, max) = grid.get_boundaries();
+    (0..100).find_map(|_| {
+        let position = Vec2::new(
+            min.x + rng.gen::<f32>() * (max.x - min.x),
+            min.y + rng.gen::<f32>() * (max.y - min.y),
+        );
+        let near_nest = colonies.iter().any(|(_, colony)| {
+            grid.distance(position, colony.nest_position) < PREDATOR_VIEW_DISTANCE * 2.0
+        });
+        (!near_nest && !grid.is_wall_at(position)).then_some(position)
+    })
+}
+
+fn spawn_predator(commands: &mut Commands, grid: &Grid, position: Vec2, rng: &mut SimulationRng) {
+    commands.spawn((
+        Transform::from_translation(position.extend(0.2)).with_rotation(Quat::from_rotation_z(
+            rng.gen::<f32>() * std::f32::consts::TAU,
+        )),
+        Predator::new(),
+        FocusableEntity::default(),
+        GridEntity::new(grid.get_grid_pos(position), GridEntityType::Predator),
+    ));
+}
+
+fn spawn_predators(
+    mut commands: Commands,
+    grid: Res<Grid>,
+    colonies: Res<Colonies>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    for _ in 0..PREDATOR_COUNT {
+        if let Some(position) = random_predator_position(&grid, &colonies, &mut rng) {
+            spawn_predator(&mut commands, &grid, position, &mut rng);
+        }
+    }
+}
+
+/// Replace the predators killed by soldiers, one at a time
+fn respawn_predators(
+    mut commands: Commands,
+    predators: Query<(), With<Predator>>,
+    grid: Res<Grid>,
+    colonies: Res<Colonies>,
+    time: Res<Time>,
+    mut rng: ResMut<SimulationRng>,
+    mut missing_for: Local<f32>,
+) {
+    if predators.iter().count() >= PREDATOR_COUNT {
+        *missing_for = 0.0;
+        return;
+    }
+    *missing_for += time.delta_secs();
+    if *missing_for < PREDATOR_RESPAWN_INTERVAL {
+        return;
+    }
+    *missing_for = 0.0;
+    if let Some(position) = random_predator_position(&grid, &colonies, &mut rng) {
+        spawn_predator(&mut commands, &grid, position, &mut rng);
+    }
+}
+
+/// Chase the closest ant in sight, wander when there is none
+fn predator_hunt(
+    mut predators: Query<(&Transform, &mut Predator)>,
+    ants: Query<&Transform, With<Ant>>,
+    grid: Res<Grid>,
+    time: Res<Time>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    let (min, max) = grid.get_boundaries();
+    let bounded = grid.topology() == Topology::Bounded;
+    for (transform, mut predator) in predators.iter_mut() {
+        let position = transform.translation.truncate();
+        predator.eating = (predator.eating - time.delta_secs()).max(0.0);
+
+        let view_cone = predator.get_view_cone(transform, &grid);
+        let prey = (predator.eating <= 0.0)
+            .then(|| {
+                grid.entities_near(GridEntityType::Ant, position, PREDATOR_VIEW_DISTANCE)
+                    .filter_map(|entity| {
+                        let ant_position = ants.get(entity).ok()?.translation.truncate();
+                        (view_cone.contains(ant_position, ANT_SIZE / 2.0)
+                            && grid.line_of_sight(position, ant_position))
+                        .then_some((entity, ant_position))
+                    })
+                    .min_by(|a, b| {
+                        grid.distance(position, a.1)
+                            .total_cmp(&grid.distance(position, b.1))
+                    })
+            })
+            .flatten();
+
+        predator.prey = prey.map(|(entity, _)| entity);
+        match prey {
+            Some((_, ant_position)) => {
+                predator.desired_direction = grid.delta(position, ant_position).normalize_or_zero();
+            }
+            None => {
+                let mut direction = predator.desired_direction;
+                direction += Vec2::new(rng.gen::<f32>() - 0.5, rng.gen::<f32>() - 0.5) * 0.5;
+                // Steer away from borders, a torus has none
+                let margin = PREDATOR_VIEW_DISTANCE;
+                if bounded {
+                    if position.x < min.x + margin {
+                        direction.x += 1.0;
+                    } else if position.x > max.x - margin {
+                        direction.x -= 1.0;
+                    }
+                    if position.y < min.y + margin {
+                        direction.y += 1.0;
+                    } else if position.y > max.y - margin {
+                        direction.y -= 1.0;
+                    }
+                }
+                predator.desired_direction = direction.normalize_or_zero();
+            }
+        }
+    }
+}
+
+fn move_predators(
+    mut predators: Query<(&mut Transform, &mut Predator)>,
+    grid: Res<Grid>,
+    time: Res<Time>,
+) {
+    let delta_secs = time.delta_secs();
+    for (mut transform, mut predator) in predators.iter_mut() {
+        let position = transform.translation.truncate();
+
+        // Turn toward the desired direction at a limited rate
+        let forward = (transform.rotation * Vec3::Y).truncate();
+        if predator.desired_direction != Vec2::ZERO {
+            let angle = forward.angle_to(predator.desired_direction);
+            let step = PREDATOR_ROTATION_SPEED * delta_secs;
+            transform.rotate_z(angle.clamp(-step, step));
+        }
+
+        let speed = if predator.prey.is_some() {
+            PREDATOR_CHASE_SPEED
+        } else {
+            PREDATOR_SPEED
+        };
+        let movement =
+            transform.rotation * Vec3::Y * speed * grid.terrain_at(position).speed() * delta_secs;
+        if grid.is_wall_at((transform.translation + movement).truncate())
+            && !grid.is_wall_at(position)
+        {
+            // Turn around instead of walking into a wall
+            transform.rotate_z(std::f32::consts::PI);
+            predator.desired_direction = -predator.desired_direction;
+        } else {
+            transform.translation += movement;
+        }
+
+        let constrained = grid.constrain(transform.translation.truncate());
+        transform.translation.x = constrained.x;
+        transform.translation.y = constrained.y;
+    }
+}
+
+/// Predators catch the ant they reach, soldiers touching a predator bite it. A catch
+/// leaves a burst of alarm for the colony of the victim
+fn predator_attack(
+    mut commands: Commands,
+    mut predators: Query<(Entity, &Transform, &mut Predator)>,
+    ants: Query<(&Transform, &ColonyId, &Caste), With<Ant>>,
+    mut pheromone_map: ResMut<PheromoneMap>,
+    pheromone_settings: Res<PheromoneSettings>,
+    castes: Res<CasteSettings>,
+    grid: Res<Grid>,
+    time: Res<Time>,
+) {
+    let contact = (PREDATOR_SIZE + ANT_SIZE) / 2.0;
+    let mut caught = Vec::new();
+    for (entity, transform, mut predator) in predators.iter_mut() {
+        let position = transform.translation.truncate();
+
+        if let Some(prey) = predator.prey {
+            if let Ok((ant_transform, colony_id, _)) = ants.get(prey) {
+                let ant_position = ant_transform.translation.truncate();
+                if !caught.contains(&prey) && grid.distance(position, ant_position) <= contact {
+                    caught.push(prey);
+                    // The selection panel may have killed it this frame too
+                    commands.entity(prey).try_despawn();
+                    pheromone_map.deposit(
+                        *colony_id,
+                        Pheromones::Alarm,
+                        grid.get_grid_pos(ant_position),
+                        pheromone_settings.max,
+                        &pheromone_settings,
+                    );
+                    predator.prey = None;
+                    predator.eating = PREDATOR_EAT_TIME;
+                }
+            }
+        }
+
+        let damage: f32 = grid
+            .entities_near(GridEntityType::Ant, position, contact)
+            .filter(|ant| !caught.contains(ant))
+            .filter_map(|ant| ants.get(ant).ok())
+            .filter(|(ant_transform, _, caste)| {
+                castes.profile(**caste).defends
+                    && grid.distance(position, ant_transform.translation.truncate()) <= contact
+            })
+            .map(|(_, _, caste)| {
+                ANT_FIGHT_DAMAGE * castes.profile(*caste).attack * time.delta_secs()
+            })
+            .sum();
+        predator.health -= damage;
+        if predator.health <= 0.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn draw_predators(
+    mut gizmos: Gizmos,
+    predators: Query<(&Transform, &Predator)>,
+    grid: Res<Grid>,
+    ui_controls: Res<UiControls>,
+) {
+    if !ui_controls.show_predators {
+        return;
+    }
+    let color = LinearRgba::from_f32_array(PREDATOR_COLOR);
+    for (transform, predator) in predators.iter() {
+        let position = transform.translation.truncate();
+        let forward = (transform.rotation * Vec3::Y).truncate();
+        gizmos.circle_2d(position, PREDATOR_SIZE / 2.0, color);
+        gizmos.line_2d(position, position + forward * PREDATOR_SIZE, color);
+        // Health left as a shrinking inner ring
+        gizmos.circle_2d(
+            position,
+            PREDATOR_SIZE / 2.0 * (predator.health / PREDATOR_HEALTH).max(0.0),
+            color.with_alpha(0.5),
+        );
+        if ui_controls.show_ant_views {
+            predator.get_view_cone(transform, &grid).draw(&mut gizmos);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use rand::{rngs::SmallRng, RngCore, SeedableRng};
+
+/// Seeds every random draw of the simulation: `--seed <number>`, random when missing.
+/// Movement still follows the frame time, so two runs with the same seed drift apart
+#[derive(Debug, Clone)]
+pub struct RngPlugin {
+    pub seed: u64,
+}
+
+impl Default for RngPlugin {
+    fn default() -> Self {
+        Self {
+            seed: rand::random(),
+        }
+    }
+}
+
+impl RngPlugin {
+    /// Read the seed option, ignoring any other argument
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut plugin = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--seed" {
+                match args.next().and_then(|seed| seed.parse().ok()) {
+                    Some(seed) => plugin.seed = seed,
+                    None => warn!("--seed expects a number"),
+                }
+            }
+        }
+        plugin
+    }
+}
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        info!("Simulation seed {}", self.seed);
+        app.insert_resource(SimulationRng::new(self.seed));
+    }
+}
+
+/// Random number generator shared by the systems, draw with [`rand::Rng`]
+#[derive(Resource, Debug)]
+pub struct SimulationRng {
+    seed: u64,
+    rng: SmallRng,
+}
+
+impl SimulationRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Generator of its own for `entity`, for parallel systems that can't share this one.
+    /// `key` is drawn from this generator once per run of the system
+    pub fn for_entity(key: u64, entity: Entity) -> SmallRng {
+        SmallRng::seed_from_u64(key ^ entity.to_bits())
+    }
+}
+
+impl RngCore for SimulationRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
@@ -0,0 +1,368 @@
+use std::fmt::Write;
+
+use bevy::{
+    color::palettes::css::{AQUA, YELLOW},
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use crate::{
+    ant::{Ant, DesiredTarget},
+    bindings::{Action, Actions},
+    caste::Caste,
+    colony::ColonyId,
+    grid::Grid,
+    tools::{ActiveTool, Tool},
+    ui::PointerOverUi,
+    utils::{cursor_to_world, window_to_world},
+    ANT_SIZE,
+};
+
+pub struct SelectionPlugin;
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BoxSelection>()
+            .add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                (
+                    box_select,
+                    selection_hotkeys,
+                    command_buttons,
+                    teleport_selection,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, (draw_selection, update_selection_panel));
+    }
+}
+
+/// Ant picked by the box selection
+#[derive(Component, Debug, Default)]
+pub struct Selected;
+
+/// Ant whose movements are recorded
+#[derive(Component, Debug, Default)]
+pub struct Tracked;
+
+/// Drag in progress, in screen and world coordinates
+#[derive(Resource, Debug, Default)]
+struct BoxSelection {
+    start: Option<(Vec2, Vec2)>,
+    /// The next world click moves the selection there
+    teleport_pending: bool,
+}
+
+/// Something to do with all the selected ants
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum GroupCommand {
+    Force(DesiredTarget),
+    Teleport,
+    Kill,
+    Track,
+}
+
+impl GroupCommand {
+    const ALL: [GroupCommand; 6] = [
+        GroupCommand::Force(DesiredTarget::NOTHING),
+        GroupCommand::Force(DesiredTarget::FOOD),
+        GroupCommand::Force(DesiredTarget::NEST),
+        GroupCommand::Teleport,
+        GroupCommand::Track,
+        GroupCommand::Kill,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            GroupCommand::Force(DesiredTarget::NEST) => "Home",
+            GroupCommand::Force(DesiredTarget::FOOD) => "Food",
+            GroupCommand::Force(_) => "Wander",
+            GroupCommand::Teleport => "Teleport",
+            GroupCommand::Kill => "Kill (Del)",
+            GroupCommand::Track => "Track",
+        }
+    }
+}
+
+#[derive(Component)]
+struct SelectionPanel;
+
+#[derive(Component)]
+struct SelectionText;
+
+fn setup(mut commands: Commands) {
+    let font = TextFont {
+        font_size: 14.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Percent(40.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.75)),
+            GlobalZIndex(i32::MAX),
+            Visibility::Hidden,
+            Interaction::default(),
+            SelectionPanel,
+        ))
+        .with_children(|p| {
+            p.spawn((Text::default(), font.clone(), SelectionText));
+            p.spawn(Node {
+                flex_wrap: FlexWrap::Wrap,
+                max_width: Val::Px(220.0),
+                column_gap: Val::Px(4.0),
+                row_gap: Val::Px(4.0),
+                ..default()
+            })
+            .with_children(|p| {
+                for command in GroupCommand::ALL {
+                    p.spawn((
+                        Button,
+                        Node {
+                            padding: UiRect::axes(Val::Px(4.0), Val::Px(2.0)),
+                            ..default()
+                        },
+                        BorderRadius::all(Val::Px(3.0)),
+                        BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                        command,
+                    ))
+                    .with_child((
+                        Text::new(command.label()),
+                        font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                }
+            });
+        });
+}
+
+/// Drag with the select tool to pick every ant inside the box, Shift adds to the selection
+fn box_select(
+    mut commands: Commands,
+    mut selection: ResMut<BoxSelection>,
+    active_tool: Res<ActiveTool>,
+    actions: Actions,
+    pointer_over_ui: Res<PointerOverUi>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    ants: Query<(Entity, &Transform), With<Ant>>,
+    selected: Query<Entity, With<Selected>>,
+) {
+    let (camera, camera_transform) = *camera;
+    let Some((cursor, world_pos)) = window.cursor_position().and_then(|cursor| {
+        window_to_world(cursor, camera, camera_transform).map(|world_pos| (cursor, world_pos))
+    }) else {
+        return;
+    };
+
+    if actions.just_pressed(Action::Select)
+        && active_tool.tool == Tool::Select
+        && !pointer_over_ui.0
+        && !actions.pressed(Action::DragPan)
+        && !selection.teleport_pending
+    {
+        selection.start = Some((cursor, world_pos));
+    }
+
+    if !actions.just_released(Action::Select) {
+        return;
+    }
+    let Some((start_cursor, start_world)) = selection.start.take() else {
+        return;
+    };
+    // A click focuses an ant instead
+    if start_cursor.distance(cursor) < 5.0 {
+        return;
+    }
+
+    if !actions.pressed(Action::ExtendSelection) {
+        for entity in selected.iter() {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+    let area = Rect::from_corners(start_world, world_pos);
+    for (entity, transform) in ants.iter() {
+        if area.contains(transform.translation.truncate()) {
+            commands.entity(entity).insert(Selected);
+        }
+    }
+}
+
+fn selection_hotkeys(
+    mut commands: Commands,
+    actions: Actions,
+    selected: Query<Entity, With<Selected>>,
+) {
+    if actions.just_pressed(Action::ClearSelection) {
+        for entity in selected.iter() {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+    if actions.just_pressed(Action::KillSelection) {
+        for entity in selected.iter() {
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
+fn command_buttons(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &GroupCommand), Changed<Interaction>>,
+    mut selection: ResMut<BoxSelection>,
+    mut selected: Query<(Entity, &mut Ant, Has<Tracked>), With<Selected>>,
+) {
+    for (interaction, command) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match command {
+            GroupCommand::Force(target) => {
+                for (_, mut ant, _) in selected.iter_mut() {
+                    ant.force_target(*target);
+                }
+            }
+            GroupCommand::Teleport => selection.teleport_pending = true,
+            GroupCommand::Kill => {
+                for (entity, _, _) in selected.iter() {
+                    commands.entity(entity).try_despawn();
+                }
+            }
+            GroupCommand::Track => {
+                // Stop tracking once every selected ant is tracked
+                let all_tracked = selected.iter().all(|(_, _, tracked)| tracked);
+                for (entity, _, _) in selected.iter() {
+                    if all_tracked {
+                        commands.entity(entity).remove::<Tracked>();
+                    } else {
+                        commands.entity(entity).insert(Tracked);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Move the selection to the clicked position, keeping its shape
+fn teleport_selection(
+    mut selection: ResMut<BoxSelection>,
+    actions: Actions,
+    pointer_over_ui: Res<PointerOverUi>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    grid: Res<Grid>,
+    mut selected: Query<&mut Transform, With<Selected>>,
+) {
+    if !selection.teleport_pending || pointer_over_ui.0 || !actions.just_pressed(Action::Select) {
+        return;
+    }
+    let (camera, camera_transform) = *camera;
+    let Some(target) = cursor_to_world(&window, camera, camera_transform) else {
+        return;
+    };
+    selection.teleport_pending = false;
+    let count = selected.iter().len();
+    if count == 0 {
+        return;
+    }
+
+    let centroid = selected
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .sum::<Vec2>()
+        / count as f32;
+    for mut transform in selected.iter_mut() {
+        let position = grid.constrain(target + transform.translation.truncate() - centroid);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+fn draw_selection(
+    mut gizmos: Gizmos,
+    selection: Res<BoxSelection>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    ants: Query<(&Transform, Has<Selected>, Has<Tracked>), Or<(With<Selected>, With<Tracked>)>>,
+) {
+    let (camera, camera_transform) = *camera;
+    if let (Some((_, start)), Some(cursor)) = (
+        selection.start,
+        cursor_to_world(&window, camera, camera_transform),
+    ) {
+        let area = Rect::from_corners(start, cursor);
+        gizmos.rect_2d(area.center(), area.size(), YELLOW);
+    }
+    for (transform, selected, tracked) in ants.iter() {
+        let position = transform.translation.truncate();
+        if selected {
+            gizmos.circle_2d(position, ANT_SIZE * 0.8, YELLOW);
+        }
+        if tracked {
+            gizmos.circle_2d(position, ANT_SIZE, AQUA);
+        }
+    }
+}
+
+/// Head counts and averages of the selected ants
+fn update_selection_panel(
+    selection: Res<BoxSelection>,
+    selected: Query<(&Ant, &Caste, &ColonyId), With<Selected>>,
+    mut panel: Single<&mut Visibility, With<SelectionPanel>>,
+    mut text: Single<&mut Text, With<SelectionText>>,
+) {
+    let count = selected.iter().len();
+    if count == 0 {
+        **panel = Visibility::Hidden;
+        return;
+    }
+    **panel = Visibility::Inherited;
+
+    let mut castes = [0; 3];
+    let mut targets = [0; DesiredTarget::ALL.len()];
+    let mut colonies = Vec::new();
+    let (mut energy, mut carrying) = (0.0, 0.0);
+    for (ant, caste, colony) in selected.iter() {
+        castes[caste.index()] += 1;
+        targets[ant.desired_target().index()] += 1;
+        if colonies.len() <= colony.0 {
+            colonies.resize(colony.0 + 1, 0);
+        }
+        colonies[colony.0] += 1;
+        energy += ant.energy();
+        carrying += ant.carrying();
+    }
+
+    let mut info = format!("Selected: {count}\n");
+    for caste in Caste::ALL {
+        let _ = write!(info, "{}s: {}  ", caste.name(), castes[caste.index()]);
+    }
+    let _ = writeln!(info);
+    for target in DesiredTarget::ALL {
+        let _ = write!(info, "{target:?}: {}  ", targets[target.index()]);
+    }
+    let _ = writeln!(info);
+    for (colony, colony_count) in colonies.iter().enumerate() {
+        if *colony_count > 0 {
+            let _ = write!(info, "Colony {}: {colony_count}  ", colony + 1);
+        }
+    }
+    let _ = writeln!(info);
+    let _ = write!(
+        info,
+        "Mean energy: {:.0}\nCarrying: {carrying:.1}",
+        energy / count as f32
+    );
+    if selection.teleport_pending {
+        let _ = write!(info, "\nClick to teleport");
+    }
+    text.0 = info;
+}
@@ -0,0 +1,264 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+use crate::{grid::Grid, WALL_COLOR};
+
+/// Ground the grid is made of, loaded from a map given on the command line: `--map <file>`
+#[derive(Debug, Clone, Default)]
+pub struct TerrainPlugin {
+    pub map: Option<PathBuf>,
+}
+
+impl TerrainPlugin {
+    /// Read the map option, ignoring any other argument
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut args = args.into_iter();
+        let mut map = None;
+        while let Some(arg) = args.next() {
+            if arg == "--map" {
+                map = args.next().map(PathBuf::from);
+            }
+        }
+        Self { map }
+    }
+}
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        let map = self.map.clone();
+        // Before anything is spawned on the ground
+        app.add_systems(PreStartup, move |mut grid: ResMut<Grid>| {
+            let Some(path) = &map else {
+                return;
+            };
+            match TerrainMap::load(path) {
+                Ok(map) => map.apply(&mut grid),
+                Err(error) => error!("Can't load map {path:?}: {error}"),
+            }
+        })
+        .add_systems(Startup, setup_background)
+        .add_systems(Update, update_background);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Terrain {
+    #[default]
+    Grass,
+    Sand,
+    Mud,
+    Water,
+}
+
+impl Terrain {
+    pub const ALL: [Terrain; 4] = [Terrain::Grass, Terrain::Sand, Terrain::Mud, Terrain::Water];
+
+    /// Walking speed relative to grass
+    pub fn speed(&self) -> f32 {
+        match self {
+            Terrain::Grass => 1.0,
+            Terrain::Sand => 0.8,
+            Terrain::Mud => 0.5,
+            Terrain::Water => 0.25,
+        }
+    }
+
+    /// How fast pheromone evaporates relative to grass
+    pub fn evaporation(&self) -> f32 {
+        match self {
+            Terrain::Grass => 1.0,
+            Terrain::Sand => 2.0,
+            Terrain::Mud => 0.5,
+            Terrain::Water => 4.0,
+        }
+    }
+
+    /// How fast pheromone spreads to the neighbouring cells relative to grass
+    pub fn diffusion(&self) -> f32 {
+        match self {
+            Terrain::Grass => 1.0,
+            Terrain::Sand => 1.5,
+            Terrain::Mud => 0.3,
+            Terrain::Water => 3.0,
+        }
+    }
+
+    /// How fast food grows back relative to grass
+    pub fn food_regrowth(&self) -> f32 {
+        match self {
+            Terrain::Grass => 1.0,
+            Terrain::Sand => 0.2,
+            Terrain::Mud => 0.6,
+            Terrain::Water => 0.0,
+        }
+    }
+
+    pub fn color(&self) -> [f32; 4] {
+        match self {
+            Terrain::Grass => [0.02, 0.04, 0.015, 1.0],
+            Terrain::Sand => [0.2, 0.16, 0.07, 1.0],
+            Terrain::Mud => [0.06, 0.035, 0.02, 1.0],
+            Terrain::Water => [0.01, 0.03, 0.1, 1.0],
+        }
+    }
+
+    /// Character standing for the terrain in map files
+    pub fn symbol(&self) -> char {
+        match self {
+            Terrain::Grass => '.',
+            Terrain::Sand => ':',
+            Terrain::Mud => '%',
+            Terrain::Water => '~',
+        }
+    }
+}
+
+/// Character standing for a wall in map files
+const WALL_SYMBOL: char = '#';
+
+/// Terrain and walls read from a text file, one line per row from north to south and one
+/// character per cell, see `Terrain::symbol` and `WALL_SYMBOL`. The map is stretched over
+/// the grid and missing cells are grass
+#[derive(Debug, Clone)]
+pub struct TerrainMap {
+    size: UVec2,
+    /// Row by row from the north, with whether the cell is a wall
+    cells: Vec<(Terrain, bool)>,
+}
+
+impl TerrainMap {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let rows: Vec<&str> = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|row| !row.is_empty())
+            .collect();
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        if width == 0 {
+            return Err("the map is empty".to_string());
+        }
+
+        let mut cells = vec![(Terrain::Grass, false); width * rows.len()];
+        for (y, row) in rows.iter().enumerate() {
+            for (x, symbol) in row.chars().enumerate() {
+                cells[x + y * width] = if symbol == WALL_SYMBOL {
+                    (Terrain::Grass, true)
+                } else {
+                    let terrain = Terrain::ALL
+                        .into_iter()
+                        .find(|terrain| terrain.symbol() == symbol)
+                        .ok_or_else(|| {
+                            format!(
+                                "unknown symbol {symbol:?} at line {}, column {}",
+                                y + 1,
+                                x + 1
+                            )
+                        })?;
+                    (terrain, false)
+                };
+            }
+        }
+        Ok(Self {
+            size: UVec2::new(width as u32, rows.len() as u32),
+            cells,
+        })
+    }
+
+    pub fn load(path: &PathBuf) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        Self::parse(&text)
+    }
+
+    /// Terrain and wall of the map cell covering a grid cell
+    pub fn at(&self, pos: UVec2, grid_size: UVec2) -> (Terrain, bool) {
+        // Grid rows go up while map rows go down
+        let x = pos.x * self.size.x / grid_size.x;
+        let y = (grid_size.y - 1 - pos.y) * self.size.y / grid_size.y;
+        self.cells[(x + y * self.size.x) as usize]
+    }
+
+    pub fn apply(&self, grid: &mut Grid) {
+        let size = grid.get_size();
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let pos = UVec2::new(x, y);
+                let (terrain, wall) = self.at(pos, size);
+                grid.set_terrain(pos, terrain);
+                grid.set_wall(pos, wall);
+            }
+        }
+    }
+}
+
+/// World-space sprite showing the terrain and the walls
+#[derive(Component)]
+struct TerrainBackground;
+
+fn setup_background(mut commands: Commands, mut images: ResMut<Assets<Image>>, grid: Res<Grid>) {
+    let size = grid.get_size();
+    let image = Image::new_fill(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    let (min, max) = grid.get_boundaries();
+    commands.spawn((
+        Sprite {
+            image: images.add(image),
+            custom_size: Some(max - min),
+            ..default()
+        },
+        // Below the pheromone heatmap
+        Transform::from_translation(((min + max) / 2.0).extend(-2.0)),
+        TerrainBackground,
+    ));
+}
+
+/// Repaint the background whenever the terrain or the walls change
+fn update_background(
+    grid: Res<Grid>,
+    background: Single<&Sprite, With<TerrainBackground>>,
+    mut images: ResMut<Assets<Image>>,
+    mut painted_revision: Local<Option<u64>>,
+) {
+    if *painted_revision == Some(grid.layout_revision()) {
+        return;
+    }
+    let Some(image) = images.get_mut(&background.image) else {
+        return;
+    };
+    *painted_revision = Some(grid.layout_revision());
+
+    let wall_color = Srgba::from(LinearRgba::from_f32_array(WALL_COLOR)).to_u8_array();
+    let size = grid.get_size();
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let pos = UVec2::new(x, y);
+            let color = if grid.is_wall(pos) {
+                wall_color
+            } else {
+                Srgba::from(LinearRgba::from_f32_array(grid.terrain(pos).color())).to_u8_array()
+            };
+            // Image rows go down while grid rows go up
+            let pixel = ((x + (size.y - 1 - y) * size.x) * 4) as usize;
+            image.data[pixel..pixel + 4].copy_from_slice(&color);
+        }
+    }
+}
@@ -0,0 +1,358 @@
+use bevy::{color::palettes::css::LIME, prelude::*, window::PrimaryWindow};
+
+use crate::{
+    bindings::{Action, Actions},
+    camera::FocusableEntity,
+    colony::{Colonies, ColonyId},
+    food::FoodSource,
+    grid::{Grid, GridEntity, GridEntityType},
+    pheromone::{PheromoneMap, PheromoneSettings, Pheromones},
+    ui::PointerOverUi,
+    utils::cursor_to_world,
+    FOOD_FOCUS_ZOOM, TOOL_BRUSH_RADIUS, TOOL_FOOD_AMOUNT,
+};
+
+pub struct ToolsPlugin;
+
+impl Plugin for ToolsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveTool>()
+            .add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                (tool_hotkeys, tool_buttons, update_toolbar, apply_tool).chain(),
+            )
+            .add_systems(Update, draw_brush);
+    }
+}
+
+/// What the paint binding, the left mouse button by default, does in the world
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tool {
+    /// Focus ants and point them to food
+    #[default]
+    Select,
+    PaintFood,
+    PaintWall,
+    PaintPheromone,
+    ErasePheromone,
+    PlaceNest,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 6] = [
+        Tool::Select,
+        Tool::PaintFood,
+        Tool::PaintWall,
+        Tool::PaintPheromone,
+        Tool::ErasePheromone,
+        Tool::PlaceNest,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Select => "Select",
+            Tool::PaintFood => "Food",
+            Tool::PaintWall => "Wall",
+            Tool::PaintPheromone => "Pheromone",
+            Tool::ErasePheromone => "Erase",
+            Tool::PlaceNest => "Nest",
+        }
+    }
+
+    fn action(&self) -> Action {
+        match self {
+            Tool::Select => Action::ToolSelect,
+            Tool::PaintFood => Action::ToolFood,
+            Tool::PaintWall => Action::ToolWall,
+            Tool::PaintPheromone => Action::ToolPheromone,
+            Tool::ErasePheromone => Action::ToolErasePheromone,
+            Tool::PlaceNest => Action::ToolNest,
+        }
+    }
+
+    /// Tool used with the erase binding, it undoes the painting tools
+    fn inverse(&self) -> Option<Tool> {
+        match self {
+            Tool::PaintPheromone => Some(Tool::ErasePheromone),
+            Tool::ErasePheromone => Some(Tool::PaintPheromone),
+            Tool::PaintFood | Tool::PaintWall => Some(*self),
+            Tool::Select | Tool::PlaceNest => None,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct ActiveTool {
+    pub tool: Tool,
+    pub brush_radius: f32,
+    /// Colony whose pheromones are painted and whose nest is placed
+    pub colony: ColonyId,
+    pub pheromone: Pheromones,
+}
+
+impl Default for ActiveTool {
+    fn default() -> Self {
+        Self {
+            tool: Tool::Select,
+            brush_radius: TOOL_BRUSH_RADIUS,
+            colony: ColonyId(0),
+            pheromone: Pheromones::LookingForHome,
+        }
+    }
+}
+
+#[derive(Component)]
+struct ToolbarText;
+
+fn setup(mut commands: Commands) {
+    let font = TextFont {
+        font_size: 14.0,
+        ..default()
+    };
+
+    // Full width row to center the toolbar at the top of the window
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        })
+        .with_children(|p| {
+            p.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(4.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::BLACK.with_alpha(0.75)),
+                GlobalZIndex(i32::MAX),
+                Interaction::default(),
+            ))
+            .with_children(|p| {
+                p.spawn(Node {
+                    column_gap: Val::Px(4.0),
+                    ..default()
+                })
+                .with_children(|p| {
+                    for (i, tool) in Tool::ALL.into_iter().enumerate() {
+                        p.spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(4.0), Val::Px(2.0)),
+                                border: UiRect::all(Val::Px(2.0)),
+                                ..default()
+                            },
+                            BorderRadius::all(Val::Px(3.0)),
+                            BorderColor(Color::NONE),
+                            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                            tool,
+                        ))
+                        .with_child((
+                            Text::new(format!("{} ({})", tool.name(), i + 1)),
+                            font.clone(),
+                            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        ));
+                    }
+                });
+                p.spawn((Text::default(), font.clone(), ToolbarText));
+            });
+        });
+}
+
+fn tool_hotkeys(actions: Actions, mut active_tool: ResMut<ActiveTool>, colonies: Res<Colonies>) {
+    for tool in Tool::ALL {
+        if actions.just_pressed(tool.action()) {
+            active_tool.tool = tool;
+        }
+    }
+    if actions.just_pressed(Action::BrushSmaller) {
+        active_tool.brush_radius = (active_tool.brush_radius - 25.0).max(25.0);
+    }
+    if actions.just_pressed(Action::BrushLarger) {
+        active_tool.brush_radius = (active_tool.brush_radius + 25.0).min(1000.0);
+    }
+    if actions.just_pressed(Action::CyclePheromone) {
+        active_tool.pheromone = match active_tool.pheromone {
+            Pheromones::LookingForFood => Pheromones::LookingForHome,
+            Pheromones::LookingForHome => Pheromones::Alarm,
+            Pheromones::Alarm => Pheromones::LookingForFood,
+        };
+    }
+    if actions.just_pressed(Action::CycleColony) && !colonies.is_empty() {
+        active_tool.colony = ColonyId((active_tool.colony.0 + 1) % colonies.len());
+    }
+}
+
+fn tool_buttons(
+    buttons: Query<(&Interaction, &Tool), Changed<Interaction>>,
+    mut active_tool: ResMut<ActiveTool>,
+) {
+    for (interaction, tool) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            active_tool.tool = *tool;
+        }
+    }
+}
+
+fn update_toolbar(
+    active_tool: Res<ActiveTool>,
+    mut buttons: Query<(&mut BorderColor, &Tool)>,
+    mut text: Single<&mut Text, With<ToolbarText>>,
+) {
+    if !active_tool.is_changed() {
+        return;
+    }
+    for (mut border, tool) in buttons.iter_mut() {
+        border.0 = if *tool == active_tool.tool {
+            LIME.into()
+        } else {
+            Color::NONE
+        };
+    }
+    text.0 = format!(
+        "Brush {:.0} ([ ])  {:?} (T)  Colony {} (Y)",
+        active_tool.brush_radius,
+        active_tool.pheromone,
+        active_tool.colony.0 + 1
+    );
+}
+
+/// World position under the cursor, unless it is over the UI
+fn cursor_world_pos(
+    window: &Window,
+    (camera, camera_transform): (&Camera, &GlobalTransform),
+    pointer_over_ui: &PointerOverUi,
+) -> Option<Vec2> {
+    if pointer_over_ui.0 {
+        return None;
+    }
+    cursor_to_world(window, camera, camera_transform)
+}
+
+fn apply_tool(
+    mut commands: Commands,
+    active_tool: Res<ActiveTool>,
+    actions: Actions,
+    pointer_over_ui: Res<PointerOverUi>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    mut grid: ResMut<Grid>,
+    mut colonies: ResMut<Colonies>,
+    mut pheromone_map: ResMut<PheromoneMap>,
+    pheromone_settings: Res<PheromoneSettings>,
+    food_sources: Query<(Entity, &Transform), With<FoodSource>>,
+    time: Res<Time>,
+) {
+    // Dragging the camera doesn't paint, whichever button it is bound to
+    if actions.pressed(Action::DragPan) {
+        return;
+    }
+    let erase = actions.pressed(Action::Erase);
+    let tool = match (actions.pressed(Action::Paint), erase) {
+        (true, _) => active_tool.tool,
+        (false, true) => match active_tool.tool.inverse() {
+            Some(tool) => tool,
+            None => return,
+        },
+        (false, false) => return,
+    };
+    let Some(position) = cursor_world_pos(&window, *camera, &pointer_over_ui) else {
+        return;
+    };
+
+    let radius = active_tool.brush_radius;
+    // Cells whose center is inside the brush
+    let cells: Vec<UVec2> = grid
+        .get_cells_in_area_from_grid(grid.get_grid_pos(position), radius)
+        .into_iter()
+        .filter(|cell| grid.distance(grid.get_world_pos(*cell), position) <= radius)
+        .collect();
+
+    match tool {
+        Tool::Select => {}
+        Tool::PaintFood if erase => {
+            for (entity, transform) in food_sources.iter() {
+                if grid.distance(transform.translation.truncate(), position) <= radius {
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+        Tool::PaintFood => {
+            let spacing = grid.get_cell_size().x / 2.0;
+            for cell in cells {
+                let cell_pos = grid.get_world_pos(cell);
+                if grid.is_wall(cell)
+                    || food_sources.iter().any(|(_, transform)| {
+                        grid.distance(transform.translation.truncate(), cell_pos) < spacing
+                    })
+                {
+                    continue;
+                }
+                commands.spawn((
+                    Transform::from_translation(cell_pos.extend(0.0)),
+                    FoodSource::new(TOOL_FOOD_AMOUNT),
+                    FocusableEntity::new(FOOD_FOCUS_ZOOM),
+                    GridEntity::new(cell, GridEntityType::Food),
+                ));
+            }
+        }
+        Tool::PaintWall => {
+            for cell in cells {
+                if grid.is_wall(cell) != erase {
+                    continue;
+                }
+                // The terrain background repaints the wall
+                grid.set_wall(cell, !erase);
+            }
+        }
+        Tool::PaintPheromone | Tool::ErasePheromone => {
+            // Fill or empty a cell in half a second
+            let amount = pheromone_settings.max * 2.0 * time.delta_secs();
+            for cell in cells {
+                if tool == Tool::PaintPheromone {
+                    pheromone_map.deposit(
+                        active_tool.colony,
+                        active_tool.pheromone,
+                        cell,
+                        amount,
+                        &pheromone_settings,
+                    );
+                } else {
+                    pheromone_map.erase(active_tool.colony, active_tool.pheromone, cell, amount);
+                }
+            }
+        }
+        Tool::PlaceNest => {
+            if actions.just_pressed(Action::Paint)
+                && !grid.is_wall_at(position)
+                && active_tool.colony.0 < colonies.len()
+            {
+                colonies.get_mut(active_tool.colony).nest_position = grid.constrain(position);
+            }
+        }
+    }
+}
+
+fn draw_brush(
+    mut gizmos: Gizmos,
+    active_tool: Res<ActiveTool>,
+    pointer_over_ui: Res<PointerOverUi>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+) {
+    if active_tool.tool == Tool::Select {
+        return;
+    }
+    if let Some(position) = cursor_world_pos(&window, *camera, &pointer_over_ui) {
+        let radius = match active_tool.tool {
+            Tool::PlaceNest => 10.0,
+            _ => active_tool.brush_radius,
+        };
+        gizmos.circle_2d(position, radius, Color::WHITE.with_alpha(0.5));
+    }
+}
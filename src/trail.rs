@@ -0,0 +1,103 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    ant::{Ant, DesiredTarget},
+    camera::FocusedEntity,
+    grid::Grid,
+    selection::Tracked,
+    ui::UiControls,
+    TRAIL_LENGTH, TRAIL_SPACING,
+};
+
+pub struct TrailPlugin;
+
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (record_trails, draw_trails).chain());
+    }
+}
+
+/// Last positions of a tracked or focused ant and its state there, the oldest first
+#[derive(Component, Debug, Default)]
+pub struct Trail {
+    points: VecDeque<(Vec2, DesiredTarget)>,
+}
+
+impl Trail {
+    pub fn points(&self) -> impl Iterator<Item = &(Vec2, DesiredTarget)> {
+        self.points.iter()
+    }
+
+    fn push(&mut self, position: Vec2, target: DesiredTarget) {
+        if self.points.len() >= TRAIL_LENGTH {
+            self.points.pop_front();
+        }
+        self.points.push_back((position, target));
+    }
+}
+
+fn record_trails(
+    mut commands: Commands,
+    focused_entity: Res<FocusedEntity>,
+    mut ants: Query<(Entity, &Transform, &Ant, Option<&mut Trail>, Has<Tracked>)>,
+) {
+    for (entity, transform, ant, trail, tracked) in ants.iter_mut() {
+        let followed = tracked || focused_entity.0 == Some(entity);
+        let position = transform.translation.truncate();
+        match (trail, followed) {
+            (Some(mut trail), true) => {
+                let moved = trail
+                    .points
+                    .back()
+                    .is_none_or(|(last, _)| last.distance(position) >= TRAIL_SPACING);
+                if moved {
+                    trail.push(position, ant.desired_target());
+                }
+            }
+            (None, true) => {
+                let mut trail = Trail::default();
+                trail.push(position, ant.desired_target());
+                commands.entity(entity).insert(trail);
+            }
+            (Some(_), false) => {
+                commands.entity(entity).remove::<Trail>();
+            }
+            (None, false) => {}
+        }
+    }
+}
+
+/// Polyline of each trail, fading out towards its oldest point
+fn draw_trails(
+    mut gizmos: Gizmos,
+    trails: Query<&Trail>,
+    grid: Res<Grid>,
+    ui_controls: Res<UiControls>,
+) {
+    if !ui_controls.show_ant_views {
+        return;
+    }
+    // Longer jumps are a wrap around the world or a teleport, not a walk
+    let max_step = grid.get_cell_size().max_element();
+    for trail in trails.iter() {
+        let len = trail.points.len();
+        for (i, ((from, target), (to, _))) in trail
+            .points
+            .iter()
+            .zip(trail.points.iter().skip(1))
+            .enumerate()
+        {
+            if from.distance(*to) > max_step {
+                continue;
+            }
+            let alpha = (i + 1) as f32 / len as f32;
+            gizmos.line_2d(
+                *from,
+                *to,
+                LinearRgba::from_f32_array(target.color()).with_alpha(alpha),
+            );
+        }
+    }
+}
@@ -1,10 +1,26 @@
 use bevy::{
-    color::palettes::css::{AQUA, LIME, RED},
+    color::palettes::css::{AQUA, LIME},
     diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    input::{
+        keyboard::{Key, KeyboardInput},
+        mouse::{MouseScrollUnit, MouseWheel},
+        ButtonState, InputSystem,
+    },
     prelude::*,
+    ui::{RelativeCursorPosition, UiSystem},
 };
 
-use crate::ant::AntSettings;
+use crate::{
+    ant::AntSettings,
+    bindings::{Action, Actions},
+    camera::{CameraFollow, CameraSettings},
+    colony::Colonies,
+    food::{FoodSettings, Seasons},
+    genome::Genome,
+    grid::Grid,
+    minimap::minimap_height,
+    pheromone::PheromoneSettings,
+};
 
 pub struct UiPlugin;
 
@@ -15,9 +31,36 @@ impl Plugin for UiPlugin {
                 show_ant_views: true,
                 show_grid: false,
                 show_pheromones: false,
+                show_nests: true,
+                show_charts: true,
+                show_predators: true,
             })
+            .init_resource::<PointerOverUi>()
+            .init_resource::<SettingInput>()
             .add_systems(Startup, setup)
-            .add_systems(Update, (counter_system, button_system));
+            .add_systems(PreUpdate, update_pointer_over_ui.after(UiSystem::Focus))
+            .add_systems(PreUpdate, setting_field_typing.after(InputSystem))
+            .add_systems(Update, (counter_system, button_system))
+            .add_systems(
+                Update,
+                (
+                    overlay_hotkeys,
+                    overlay_toggle_buttons,
+                    update_overlay_toggles,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    slider_system,
+                    step_button_system,
+                    setting_field_focus,
+                    update_settings_panel,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, scroll_settings_panel);
     }
 }
 
@@ -29,13 +72,266 @@ pub struct UiControls {
     pub show_grid: bool,
     pub show_ant_views: bool,
     pub show_pheromones: bool,
+    pub show_nests: bool,
+    pub show_charts: bool,
+    pub show_predators: bool,
 }
 
+/// Whether the cursor is over an interactive UI node, world clicks should be ignored then
+#[derive(Resource, Default)]
+pub struct PointerOverUi(pub bool);
+
+/// Debug overlay that can be switched on and off from the UI or the keyboard
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum Overlay {
+    Grid,
+    AntViews,
+    Pheromones,
+    Nests,
+    Charts,
+    Predators,
+}
+
+impl Overlay {
+    const ALL: [Overlay; 6] = [
+        Overlay::Grid,
+        Overlay::AntViews,
+        Overlay::Pheromones,
+        Overlay::Nests,
+        Overlay::Charts,
+        Overlay::Predators,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Overlay::Grid => "Grid (G)",
+            Overlay::AntViews => "Views (V)",
+            Overlay::Pheromones => "Pheromones (P)",
+            Overlay::Nests => "Nests (N)",
+            Overlay::Charts => "Charts (C)",
+            Overlay::Predators => "Predators (R)",
+        }
+    }
+
+    fn action(&self) -> Action {
+        match self {
+            Overlay::Grid => Action::ToggleGrid,
+            Overlay::AntViews => Action::ToggleAntViews,
+            Overlay::Pheromones => Action::TogglePheromones,
+            Overlay::Nests => Action::ToggleNests,
+            Overlay::Charts => Action::ToggleCharts,
+            Overlay::Predators => Action::TogglePredators,
+        }
+    }
+
+    fn flag<'a>(&self, controls: &'a mut UiControls) -> &'a mut bool {
+        match self {
+            Overlay::Grid => &mut controls.show_grid,
+            Overlay::AntViews => &mut controls.show_ant_views,
+            Overlay::Pheromones => &mut controls.show_pheromones,
+            Overlay::Nests => &mut controls.show_nests,
+            Overlay::Charts => &mut controls.show_charts,
+            Overlay::Predators => &mut controls.show_predators,
+        }
+    }
+}
+
+/// A tunable of the simulation shown in the settings panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Setting {
+    ViewDistance,
+    ViewAngle,
+    Speed,
+    NestSize,
+    ScentRadius,
+    ScentFalloff,
+    PheromoneDecay,
+    PheromoneDiffusion,
+    PheromoneMax,
+    HeatmapIntensity,
+    HeatmapOpacity,
+    CameraSmoothing,
+    DirectorSmoothing,
+}
+
+impl Setting {
+    const ALL: [Setting; 13] = [
+        Setting::ViewDistance,
+        Setting::ViewAngle,
+        Setting::Speed,
+        Setting::NestSize,
+        Setting::ScentRadius,
+        Setting::ScentFalloff,
+        Setting::PheromoneDecay,
+        Setting::PheromoneDiffusion,
+        Setting::PheromoneMax,
+        Setting::HeatmapIntensity,
+        Setting::HeatmapOpacity,
+        Setting::CameraSmoothing,
+        Setting::DirectorSmoothing,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Setting::ViewDistance => "View distance",
+            Setting::ViewAngle => "View angle",
+            Setting::Speed => "Speed",
+            Setting::NestSize => "Nest size",
+            Setting::ScentRadius => "Scent radius",
+            Setting::ScentFalloff => "Scent falloff",
+            Setting::PheromoneDecay => "Pheromone kept per second",
+            Setting::PheromoneDiffusion => "Pheromone spread per second",
+            Setting::PheromoneMax => "Pheromone max",
+            Setting::HeatmapIntensity => "Heatmap intensity",
+            Setting::HeatmapOpacity => "Heatmap opacity",
+            Setting::CameraSmoothing => "Camera smoothing",
+            Setting::DirectorSmoothing => "Director smoothing",
+        }
+    }
+
+    fn range(&self) -> (f32, f32) {
+        match self {
+            Setting::ViewDistance => (20.0, 500.0),
+            Setting::ViewAngle => (0.1, std::f32::consts::TAU),
+            Setting::Speed => (10.0, 500.0),
+            Setting::NestSize => (20.0, 400.0),
+            Setting::ScentRadius => (10.0, 300.0),
+            Setting::ScentFalloff => (0.1, 5.0),
+            Setting::PheromoneDecay => (0.5, 1.0),
+            Setting::PheromoneDiffusion => (0.0, 1.0),
+            Setting::PheromoneMax => (0.1, 10.0),
+            Setting::HeatmapIntensity => (0.1, 10.0),
+            Setting::HeatmapOpacity => (0.0, 1.0),
+            Setting::CameraSmoothing | Setting::DirectorSmoothing => (0.1, 20.0),
+        }
+    }
+
+    /// Change applied by the - and + buttons
+    fn step(&self) -> f32 {
+        match self {
+            Setting::ViewDistance | Setting::Speed | Setting::NestSize | Setting::ScentRadius => {
+                10.0
+            }
+            Setting::ViewAngle => 5f32.to_radians(),
+            Setting::PheromoneDecay | Setting::PheromoneDiffusion => 0.01,
+            Setting::ScentFalloff
+            | Setting::PheromoneMax
+            | Setting::HeatmapIntensity
+            | Setting::CameraSmoothing
+            | Setting::DirectorSmoothing => 0.1,
+            Setting::HeatmapOpacity => 0.05,
+        }
+    }
+
+    fn get(
+        &self,
+        ants: &AntSettings,
+        pheromones: &PheromoneSettings,
+        camera: &CameraSettings,
+    ) -> f32 {
+        match self {
+            Setting::ViewDistance => ants.view_distance,
+            Setting::ViewAngle => ants.view_angle,
+            Setting::Speed => ants.speed,
+            Setting::NestSize => ants.nest_size,
+            Setting::ScentRadius => ants.scent_radius,
+            Setting::ScentFalloff => ants.scent_falloff,
+            Setting::PheromoneDecay => pheromones.decay,
+            Setting::PheromoneDiffusion => pheromones.diffusion,
+            Setting::PheromoneMax => pheromones.max,
+            Setting::HeatmapIntensity => pheromones.heatmap_intensity,
+            Setting::HeatmapOpacity => pheromones.heatmap_opacity,
+            Setting::CameraSmoothing => camera.smoothing,
+            Setting::DirectorSmoothing => camera.director_smoothing,
+        }
+    }
+
+    fn set(
+        &self,
+        ants: &mut AntSettings,
+        pheromones: &mut PheromoneSettings,
+        camera: &mut CameraSettings,
+        value: f32,
+    ) {
+        let (min, max) = self.range();
+        let value = value.clamp(min, max);
+        match self {
+            Setting::ViewDistance => ants.view_distance = value,
+            Setting::ViewAngle => ants.view_angle = value,
+            Setting::Speed => ants.speed = value,
+            Setting::NestSize => ants.nest_size = value,
+            Setting::ScentRadius => ants.scent_radius = value,
+            Setting::ScentFalloff => ants.scent_falloff = value,
+            Setting::PheromoneDecay => pheromones.decay = value,
+            Setting::PheromoneDiffusion => pheromones.diffusion = value,
+            Setting::PheromoneMax => pheromones.max = value,
+            Setting::HeatmapIntensity => pheromones.heatmap_intensity = value,
+            Setting::HeatmapOpacity => pheromones.heatmap_opacity = value,
+            Setting::CameraSmoothing => camera.smoothing = value,
+            Setting::DirectorSmoothing => camera.director_smoothing = value,
+        }
+    }
+
+    /// Value typed in the units shown by `format`
+    fn parse(&self, text: &str) -> Option<f32> {
+        let value: f32 = text.trim().parse().ok()?;
+        match self {
+            Setting::ViewAngle => Some(value.to_radians()),
+            _ => Some(value),
+        }
+    }
+
+    fn format(&self, value: f32) -> String {
+        match self {
+            Setting::ViewAngle => format!("{:.0}°", value.to_degrees()),
+            Setting::PheromoneDecay => format!("{value:.3}"),
+            Setting::PheromoneDiffusion => format!("{value:.2}"),
+            Setting::ScentFalloff
+            | Setting::PheromoneMax
+            | Setting::HeatmapIntensity
+            | Setting::CameraSmoothing
+            | Setting::DirectorSmoothing => format!("{value:.1}"),
+            Setting::HeatmapOpacity => format!("{value:.2}"),
+            _ => format!("{value:.0}"),
+        }
+    }
+}
+
+#[derive(Component)]
+struct SettingSlider(Setting);
+
+#[derive(Component)]
+struct SettingSliderFill(Setting);
+
+/// Field showing the value of a setting, click it to type a new one
+#[derive(Component)]
+struct SettingField(Setting);
+
+#[derive(Component)]
+struct SettingsPanel;
+
+#[derive(Component)]
+struct SettingValueText(Setting);
+
+/// Setting being typed in, Enter applies it and Escape cancels
+#[derive(Resource, Default)]
+struct SettingInput {
+    editing: Option<Setting>,
+    text: String,
+}
+
+/// Button nudging a setting by its step, in the given direction
+#[derive(Component)]
+struct SettingStepButton(Setting, f32);
+
+/// Index of the first per colony value in the stats text
+const COLONY_STATS_SPAN: usize = 10;
+
 const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.75, 0.35);
 
-fn setup(mut commands: Commands) {
+fn setup(mut commands: Commands, colonies: Res<Colonies>, grid: Res<Grid>) {
     let font = TextFont {
         font_size: 14.0,
         ..Default::default()
@@ -50,6 +346,7 @@ fn setup(mut commands: Commands) {
             },
             BackgroundColor(Color::BLACK.with_alpha(0.75)),
             GlobalZIndex(i32::MAX),
+            Interaction::default(),
         ))
         .with_children(|p| {
             p.spawn((Text::default(), StatsText)).with_children(|p| {
@@ -77,53 +374,235 @@ fn setup(mut commands: Commands) {
                     TextColor(LIME.into()),
                 ));
                 p.spawn((TextSpan::new(""), font.clone(), TextColor(AQUA.into())));
-            });
-            p.spawn(Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            })
-            .with_children(|parent| {
-                parent
-                    .spawn((
-                        Button,
-                        Node {
-                            width: Val::Px(150.0),
-                            height: Val::Px(65.0),
-                            border: UiRect::all(Val::Px(5.0)),
-                            // horizontally center child text
-                            justify_content: JustifyContent::Center,
-                            // vertically center child text
-                            align_items: AlignItems::Center,
-                            ..default()
-                        },
-                        BorderColor(Color::BLACK),
-                        BorderRadius::MAX,
-                        BackgroundColor(NORMAL_BUTTON),
-                    ))
-                    .with_child((
-                        Text::new("Button"),
+                // One line per colony, starting at span `COLONY_STATS_SPAN`
+                for (colony_id, colony) in colonies.iter() {
+                    p.spawn((
+                        TextSpan::new(format!("\nColony {}: ", colony_id.0 + 1)),
                         font.clone(),
-                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        TextColor(LinearRgba::from_f32_array(colony.color).into()),
                     ));
+                    p.spawn((TextSpan::new(""), font.clone(), TextColor(AQUA.into())));
+                }
+                p.spawn((
+                    TextSpan::new("\nGenome: "),
+                    font.clone(),
+                    TextColor(LIME.into()),
+                ));
+                p.spawn((TextSpan::new(""), font.clone(), TextColor(AQUA.into())));
+                p.spawn((
+                    TextSpan::new("\nCamera (M): "),
+                    font.clone(),
+                    TextColor(LIME.into()),
+                ));
+                p.spawn((TextSpan::new(""), font.clone(), TextColor(AQUA.into())));
+                p.spawn((
+                    TextSpan::new("\nSeason: "),
+                    font.clone(),
+                    TextColor(LIME.into()),
+                ));
+                p.spawn((TextSpan::new(""), font.clone(), TextColor(AQUA.into())));
             });
         });
+
+    // Settings panel on the right side of the window, it stops above the minimap and scrolls
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                right: Val::Px(0.0),
+                bottom: Val::Px(minimap_height(&grid) + 6.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
+                ..default()
+            },
+            GlobalZIndex(i32::MAX),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Node {
+                    max_height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                BackgroundColor(Color::BLACK.with_alpha(0.75)),
+                ScrollPosition::default(),
+                Interaction::default(),
+                RelativeCursorPosition::default(),
+                SettingsPanel,
+            ))
+            .with_children(|p| {
+                p.spawn(Node {
+                    flex_wrap: FlexWrap::Wrap,
+                    max_width: Val::Px(200.0),
+                    column_gap: Val::Px(4.0),
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                })
+                .with_children(|p| {
+                    for overlay in Overlay::ALL {
+                        p.spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(4.0), Val::Px(2.0)),
+                                border: UiRect::all(Val::Px(2.0)),
+                                ..default()
+                            },
+                            BorderRadius::all(Val::Px(3.0)),
+                            BorderColor(NORMAL_BUTTON),
+                            BackgroundColor(NORMAL_BUTTON),
+                            overlay,
+                        ))
+                        .with_child((
+                            Text::new(overlay.label()),
+                            font.clone(),
+                            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        ));
+                    }
+                });
+                for setting in Setting::ALL {
+                    spawn_setting_row(p, setting, &font);
+                }
+            });
+        });
+}
+
+fn spawn_setting_row(p: &mut ChildBuilder, setting: Setting, font: &TextFont) {
+    p.spawn(Node {
+        flex_direction: FlexDirection::Column,
+        ..default()
+    })
+    .with_children(|p| {
+        p.spawn(Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|p| {
+            p.spawn((
+                Text::new(format!("{}:", setting.label())),
+                font.clone(),
+                TextColor(LIME.into()),
+            ));
+            p.spawn((
+                Node {
+                    min_width: Val::Px(50.0),
+                    padding: UiRect::horizontal(Val::Px(3.0)),
+                    border: UiRect::all(Val::Px(1.0)),
+                    ..default()
+                },
+                BorderColor(NORMAL_BUTTON),
+                BackgroundColor(NORMAL_BUTTON),
+                Interaction::default(),
+                SettingField(setting),
+            ))
+            .with_child((
+                Text::default(),
+                font.clone(),
+                TextColor(AQUA.into()),
+                SettingValueText(setting),
+            ));
+        });
+        p.spawn(Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|p| {
+            spawn_step_button(p, setting, -1.0, font);
+            // Slider track, the fill shows the current value
+            p.spawn((
+                Node {
+                    width: Val::Px(150.0),
+                    height: Val::Px(12.0),
+                    ..default()
+                },
+                BackgroundColor(NORMAL_BUTTON),
+                Interaction::default(),
+                RelativeCursorPosition::default(),
+                SettingSlider(setting),
+            ))
+            .with_child((
+                Node {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(AQUA.into()),
+                SettingSliderFill(setting),
+            ));
+            spawn_step_button(p, setting, 1.0, font);
+        });
+    });
+}
+
+fn spawn_step_button(p: &mut ChildBuilder, setting: Setting, direction: f32, font: &TextFont) {
+    p.spawn((
+        Button,
+        Node {
+            width: Val::Px(20.0),
+            height: Val::Px(20.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BorderRadius::all(Val::Px(3.0)),
+        BackgroundColor(NORMAL_BUTTON),
+        SettingStepButton(setting, direction),
+    ))
+    .with_child((
+        Text::new(if direction < 0.0 { "-" } else { "+" }),
+        font.clone(),
+        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+    ));
 }
 
 fn counter_system(
     diagnostics: Res<DiagnosticsStore>,
-    ants_settings: Res<AntSettings>,
+    colonies: Res<Colonies>,
+    follow: Res<CameraFollow>,
+    seasons: Res<Seasons>,
+    food_settings: Res<FoodSettings>,
     query: Single<Entity, With<StatsText>>,
     mut writer: TextUiWriter,
 ) {
     let text = *query;
 
-    if ants_settings.is_changed() {
-        *writer.text(text, 2) = ants_settings.n_ants.to_string();
+    if colonies.is_changed() {
+        let population: usize = colonies.iter().map(|(_, colony)| colony.population).sum();
+        *writer.text(text, 2) = population.to_string();
+        for (colony_id, colony) in colonies.iter() {
+            let [workers, scouts, soldiers] = colony.caste_population;
+            *writer.text(text, COLONY_STATS_SPAN + 2 * colony_id.0) = format!(
+                "{} ants ({workers} workers, {scouts} scouts, {soldiers} soldiers), {:.1} food",
+                colony.population, colony.food_stored
+            );
+        }
     }
 
+    // Mean and standard deviation of every trait, as "mean±std"
+    let genome_stats = Genome::DIAGNOSTICS
+        .iter()
+        .zip(["view", "noise", "deposit", "follow"])
+        .filter_map(|((mean, std_dev), name)| {
+            let mean = diagnostics.get(mean)?.value()?;
+            let std_dev = diagnostics.get(std_dev)?.value()?;
+            Some(format!("{name} {mean:.2}±{std_dev:.2}"))
+        })
+        .collect::<Vec<_>>();
+    *writer.text(text, COLONY_STATS_SPAN + 2 * colonies.len()) = genome_stats.join(", ");
+    if follow.is_changed() {
+        *writer.text(text, COLONY_STATS_SPAN + 2 * colonies.len() + 2) = follow.label();
+    }
+    *writer.text(text, COLONY_STATS_SPAN + 2 * colonies.len() + 4) = format!(
+        "{}, food x{:.1}",
+        seasons.name(&food_settings),
+        seasons.abundance(&food_settings)
+    );
+
     if let Some(fps) = diagnostics.get(&FrameTimeDiagnosticsPlugin::FPS) {
         if let Some(raw) = fps.value() {
             *writer.text(text, 4) = format!("{raw:.2}");
@@ -139,34 +618,237 @@ fn counter_system(
 
 fn button_system(
     mut interaction_query: Query<
-        (
-            &Interaction,
-            &mut BackgroundColor,
-            &mut BorderColor,
-            &Children,
-        ),
+        (&Interaction, &mut BackgroundColor),
         (Changed<Interaction>, With<Button>),
     >,
-    mut text_query: Query<&mut Text>,
 ) {
-    for (interaction, mut color, mut border_color, children) in &mut interaction_query {
-        let mut text = text_query.get_mut(children[0]).unwrap();
-        match *interaction {
-            Interaction::Pressed => {
-                **text = "Press".to_string();
-                *color = PRESSED_BUTTON.into();
-                border_color.0 = RED.into();
+    for (interaction, mut color) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+    }
+}
+
+fn update_pointer_over_ui(interactions: Query<&Interaction>, mut pointer: ResMut<PointerOverUi>) {
+    pointer.0 = interactions
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+}
+
+fn overlay_hotkeys(actions: Actions, mut ui_controls: ResMut<UiControls>) {
+    for overlay in Overlay::ALL {
+        if actions.just_pressed(overlay.action()) {
+            let flag = overlay.flag(&mut ui_controls);
+            *flag = !*flag;
+        }
+    }
+}
+
+fn overlay_toggle_buttons(
+    buttons: Query<(&Interaction, &Overlay), Changed<Interaction>>,
+    mut ui_controls: ResMut<UiControls>,
+) {
+    for (interaction, overlay) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            let flag = overlay.flag(&mut ui_controls);
+            *flag = !*flag;
+        }
+    }
+}
+
+/// Outline the toggle buttons of the overlays that are shown
+fn update_overlay_toggles(
+    mut ui_controls: ResMut<UiControls>,
+    mut buttons: Query<(&mut BorderColor, &Overlay)>,
+) {
+    if !ui_controls.is_changed() {
+        return;
+    }
+    for (mut border, overlay) in buttons.iter_mut() {
+        border.0 = if *overlay.flag(ui_controls.bypass_change_detection()) {
+            LIME.into()
+        } else {
+            NORMAL_BUTTON
+        };
+    }
+}
+
+/// The mouse wheel scrolls the settings panel when it is taller than the window
+fn scroll_settings_panel(
+    mut wheel: EventReader<MouseWheel>,
+    panel: Single<(&RelativeCursorPosition, &mut ScrollPosition), With<SettingsPanel>>,
+) {
+    let (cursor, mut scroll) = panel.into_inner();
+    for event in wheel.read() {
+        if !cursor.mouse_over() {
+            continue;
+        }
+        let lines = match event.unit {
+            MouseScrollUnit::Line => event.y * 20.0,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        scroll.offset_y -= lines;
+    }
+}
+
+/// Dragging on a slider sets its setting from the cursor position
+fn slider_system(
+    sliders: Query<(&Interaction, &RelativeCursorPosition, &SettingSlider)>,
+    mut ants_settings: ResMut<AntSettings>,
+    mut pheromone_settings: ResMut<PheromoneSettings>,
+    mut camera_settings: ResMut<CameraSettings>,
+) {
+    for (interaction, cursor, slider) in sliders.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(position) = cursor.normalized {
+            let (min, max) = slider.0.range();
+            slider.0.set(
+                &mut ants_settings,
+                &mut pheromone_settings,
+                &mut camera_settings,
+                min + position.x.clamp(0.0, 1.0) * (max - min),
+            );
+        }
+    }
+}
+
+fn step_button_system(
+    buttons: Query<(&Interaction, &SettingStepButton), Changed<Interaction>>,
+    mut ants_settings: ResMut<AntSettings>,
+    mut pheromone_settings: ResMut<PheromoneSettings>,
+    mut camera_settings: ResMut<CameraSettings>,
+) {
+    for (interaction, SettingStepButton(setting, direction)) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            let value = setting.get(&ants_settings, &pheromone_settings, &camera_settings);
+            setting.set(
+                &mut ants_settings,
+                &mut pheromone_settings,
+                &mut camera_settings,
+                value + setting.step() * direction,
+            );
+        }
+    }
+}
+
+/// Clicking a field starts typing in it, clicking anywhere else cancels
+fn setting_field_focus(
+    fields: Query<(Ref<Interaction>, &SettingField)>,
+    actions: Actions,
+    mut input: ResMut<SettingInput>,
+) {
+    let clicked = fields
+        .iter()
+        .find(|(interaction, _)| interaction.is_changed() && **interaction == Interaction::Pressed)
+        .map(|(_, field)| field.0);
+    if let Some(setting) = clicked {
+        if input.editing != Some(setting) {
+            input.editing = Some(setting);
+            input.text.clear();
+        }
+    } else if actions.just_pressed(Action::Select)
+        && input.editing.is_some()
+        && fields
+            .iter()
+            .all(|(interaction, _)| *interaction != Interaction::Pressed)
+    {
+        input.editing = None;
+    }
+}
+
+/// Typing into the focused field, the keys don't reach the rest of the app meanwhile
+fn setting_field_typing(
+    mut input: ResMut<SettingInput>,
+    mut events: EventReader<KeyboardInput>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut ants_settings: ResMut<AntSettings>,
+    mut pheromone_settings: ResMut<PheromoneSettings>,
+    mut camera_settings: ResMut<CameraSettings>,
+) {
+    let Some(setting) = input.editing else {
+        events.clear();
+        return;
+    };
+    for event in events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        // The typed key doesn't trigger its bound action, other held keys keep working
+        keys.reset(event.key_code);
+        match &event.logical_key {
+            Key::Enter => {
+                if let Some(value) = setting.parse(&input.text) {
+                    setting.set(
+                        &mut ants_settings,
+                        &mut pheromone_settings,
+                        &mut camera_settings,
+                        value,
+                    );
+                }
+                input.editing = None;
+                break;
             }
-            Interaction::Hovered => {
-                **text = "Hover".to_string();
-                *color = HOVERED_BUTTON.into();
-                border_color.0 = Color::WHITE;
+            Key::Escape => {
+                input.editing = None;
+                break;
             }
-            Interaction::None => {
-                **text = "Button".to_string();
-                *color = NORMAL_BUTTON.into();
-                border_color.0 = Color::BLACK;
+            Key::Backspace => {
+                input.text.pop();
             }
+            Key::Character(typed) => input.text.extend(
+                typed
+                    .chars()
+                    .filter(|c| c.is_ascii_digit() || matches!(c, '.' | '-')),
+            ),
+            _ => {}
         }
     }
 }
+
+/// Keep the panel in sync with the settings, whoever changed them
+fn update_settings_panel(
+    ants_settings: Res<AntSettings>,
+    pheromone_settings: Res<PheromoneSettings>,
+    camera_settings: Res<CameraSettings>,
+    input: Res<SettingInput>,
+    mut fills: Query<(&mut Node, &SettingSliderFill)>,
+    mut fields: Query<(&mut BorderColor, &SettingField)>,
+    mut texts: Query<(&mut Text, &SettingValueText)>,
+) {
+    if !ants_settings.is_changed()
+        && !pheromone_settings.is_changed()
+        && !camera_settings.is_changed()
+        && !input.is_changed()
+    {
+        return;
+    }
+    for (mut node, fill) in fills.iter_mut() {
+        let (min, max) = fill.0.range();
+        let value = fill
+            .0
+            .get(&ants_settings, &pheromone_settings, &camera_settings);
+        node.width = Val::Percent((value - min) / (max - min) * 100.0);
+    }
+    for (mut border, field) in fields.iter_mut() {
+        border.0 = if input.editing == Some(field.0) {
+            LIME.into()
+        } else {
+            NORMAL_BUTTON
+        };
+    }
+    for (mut text, value_text) in texts.iter_mut() {
+        **text = if input.editing == Some(value_text.0) {
+            format!("{}_", input.text)
+        } else {
+            value_text.0.format(value_text.0.get(
+                &ants_settings,
+                &pheromone_settings,
+                &camera_settings,
+            ))
+        };
+    }
+}
@@ -1,11 +1,13 @@
+use std::f32::consts::{PI, TAU};
+
 use bevy::{
     color::{Color, ColorToComponents, LinearRgba},
     math::{Mat2, Vec2},
-    prelude::{Gizmos, Transform},
+    prelude::{Camera, Gizmos, GlobalTransform},
     window::Window,
 };
 
-use crate::DEBUG_ANT_VIEW_COLOR;
+use crate::{DEBUG_ANT_VIEW_COLOR, VIEW_CONE_SAMPLES};
 
 #[derive(Debug, Clone, Copy)]
 pub struct ViewCone {
@@ -16,6 +18,7 @@ pub struct ViewCone {
     segments: usize,
     color: Color,
     direction: Vec2,
+    wrap_size: Option<Vec2>,
 }
 
 impl ViewCone {
@@ -28,6 +31,7 @@ impl ViewCone {
             color: LinearRgba::from_f32_array(DEBUG_ANT_VIEW_COLOR).into(),
             direction: Vec2::Y,
             rotation,
+            wrap_size: None,
         }
     }
     pub fn color<C>(&mut self, color: C) -> &Self
@@ -45,6 +49,11 @@ impl ViewCone {
         self.direction = direction.normalize();
         self
     }
+    /// Measure distances across the edges of a wrapping world of the given size
+    pub fn wrap_around(&mut self, wrap_size: Option<Vec2>) -> &Self {
+        self.wrap_size = wrap_size;
+        self
+    }
 
     /// Draw the view cone using gizmos
     pub fn draw(&self, gizmos: &mut Gizmos) {
@@ -86,52 +95,125 @@ impl ViewCone {
         );
     }
 
-    /// Check if a given point is inside the view cone
-    pub fn contains(&self, point: Vec2, area: f32) -> bool {
-        // Calculate the vector from the cone's center to the point
-        let to_point = point - self.center;
+    /// Offset from the cone's center to a point, across the edges of a wrapping world
+    fn offset(&self, point: Vec2) -> Vec2 {
+        match self.wrap_size {
+            Some(size) => wrap_delta(point - self.center, size),
+            None => point - self.center,
+        }
+    }
+
+    fn facing(&self) -> Vec2 {
+        Mat2::from_angle(self.rotation) * self.direction
+    }
 
-        // Check if the point is within the radius plus the area
+    /// Whether an offset from the center is inside the cone
+    fn contains_offset(&self, offset: Vec2) -> bool {
+        offset.length_squared() <= self.radius * self.radius
+            && (self.view_angle >= TAU
+                || self.facing().angle_to(offset).abs() <= self.view_angle / 2.0)
+    }
+
+    /// Check if any part of the circle of radius `area` around `point` is inside the view cone
+    pub fn contains(&self, point: Vec2, area: f32) -> bool {
+        let to_point = self.offset(point);
         if to_point.length_squared() > (self.radius + area) * (self.radius + area) {
             return false; // Point is outside the radius plus the area
         }
 
-        // Normalize the direction vector
-        let direction_norm = Mat2::from_angle(self.rotation) * self.direction;
-
-        // Calculate the angle between the direction and the point
-        let angle_to_point = direction_norm.angle_to(to_point);
+        // Within the view angle, being close enough is all it takes
+        let half_angle = self.view_angle / 2.0;
+        if half_angle >= PI || self.facing().angle_to(to_point).abs() <= half_angle {
+            return true;
+        }
 
-        // Check if the point's angle is within the cone's view angle
-        angle_to_point.abs() <= self.view_angle / 2.0
+        // Otherwise the closest part of the cone is one of its straight edges
+        [-half_angle, half_angle].into_iter().any(|angle| {
+            let edge = Mat2::from_angle(angle) * self.facing() * self.radius;
+            distance_to_segment(to_point, Vec2::ZERO, edge) <= area
+        })
     }
-}
 
-// Convert screen coordinates to world coordinates
-pub fn window_to_world(
-    cursor_position: Vec2,
-    window: &Window,
-    camera_transform: &Transform,
-) -> Vec2 {
-    // Get the window size
-    let window_size = Vec2::new(window.width(), window.height());
+    /// Share of the circle of radius `area` around `point` inside the view cone, from 0 to 1
+    pub fn visible_fraction(&self, point: Vec2, area: f32) -> f32 {
+        if !self.contains(point, area) {
+            return 0.0;
+        }
+        let to_point = self.offset(point);
+        let distance = to_point.length();
+        if area <= 0.0 {
+            return 1.0;
+        }
+
+        // Entirely inside, no need to sample
+        let half_angle = self.view_angle / 2.0;
+        if distance + area <= self.radius
+            && (half_angle >= PI
+                || (distance > area
+                    && self.facing().angle_to(to_point).abs() + (area / distance).asin()
+                        <= half_angle))
+        {
+            return 1.0;
+        }
+
+        // Count samples spread evenly over the circle along a sunflower spiral
+        let golden_angle = PI * (3.0 - 5f32.sqrt());
+        let inside = (0..VIEW_CONE_SAMPLES)
+            .filter(|&i| {
+                let sample_radius = area * ((i as f32 + 0.5) / VIEW_CONE_SAMPLES as f32).sqrt();
+                let sample = Vec2::from_angle(i as f32 * golden_angle) * sample_radius;
+                self.contains_offset(to_point + sample)
+            })
+            .count();
+        inside as f32 / VIEW_CONE_SAMPLES as f32
+    }
 
-    // Normalize the cursor position to screen space [-1, 1]
-    let normalized_cursor = (cursor_position / window_size) * 2.0 - Vec2::ONE;
+    /// Whether a circle of radius `blocker_radius` around `blocker` hides `point` from the
+    /// center of the cone
+    pub fn occludes(&self, blocker: Vec2, blocker_radius: f32, point: Vec2) -> bool {
+        let to_blocker = self.offset(blocker);
+        let to_point = self.offset(point);
+        to_blocker.length_squared() < to_point.length_squared()
+            && distance_to_segment(to_blocker, Vec2::ZERO, to_point) < blocker_radius
+    }
+}
 
-    // Invert the Y-axis (screen to world)
-    let inverted_cursor = Vec2::new(normalized_cursor.x, -normalized_cursor.y);
+/// Distance from `point` to the closest point of the segment from `start` to `end`
+pub fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared == 0.0 {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(segment) / length_squared).clamp(0.0, 1.0);
+    point.distance(start + segment * t)
+}
 
-    // Adjust for the camera's scale (zoom level)
-    let scaled_cursor = Vec2::new(
-        inverted_cursor.x * (window_size.x / 2.0) * camera_transform.scale.x,
-        inverted_cursor.y * (window_size.y / 2.0) * camera_transform.scale.y,
-    );
+/// World position shown at a point of the window, `None` when the camera can't tell
+pub fn window_to_world(
+    window_position: Vec2,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> Option<Vec2> {
+    camera
+        .viewport_to_world_2d(camera_transform, window_position)
+        .ok()
+}
 
-    // Apply the camera's translation (pan)
-    let world_position = scaled_cursor + camera_transform.translation.truncate();
+/// World position under the cursor, if it is inside the window
+pub fn cursor_to_world(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> Option<Vec2> {
+    window
+        .cursor_position()
+        .and_then(|cursor| window_to_world(cursor, camera, camera_transform))
+}
 
-    world_position
+/// Shortest equivalent of `delta` in a world that wraps around every `size`
+pub fn wrap_delta(delta: Vec2, size: Vec2) -> Vec2 {
+    delta - size * (delta / size).round()
 }
 
 pub fn square<T>(x: T) -> T
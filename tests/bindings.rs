@@ -0,0 +1,50 @@
+//! Reading the key bindings file
+
+use ant_behaviour::bindings::{Action, Binding, InputBindings};
+use bevy::prelude::{KeyCode, MouseButton};
+
+#[test]
+fn config_lines_replace_the_bindings_of_their_action() {
+    let mut bindings = InputBindings::default();
+    let errors = bindings.apply_config(
+        "# camera\n\
+         pan_up = KeyI, ArrowUp  # comment\n\
+         \n\
+         paint = MouseRight\n\
+         screenshot =\n",
+    );
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(
+        bindings.get(Action::PanUp),
+        [Binding::Key(KeyCode::KeyI), Binding::Key(KeyCode::ArrowUp)]
+    );
+    assert_eq!(
+        bindings.get(Action::Paint),
+        [Binding::Mouse(MouseButton::Right)]
+    );
+    assert!(bindings.get(Action::Screenshot).is_empty());
+    assert_eq!(
+        bindings.get(Action::PanDown),
+        InputBindings::default().get(Action::PanDown)
+    );
+}
+
+#[test]
+fn malformed_lines_are_reported_and_skipped() {
+    let mut bindings = InputBindings::default();
+    let errors = bindings.apply_config(
+        "pan_up KeyI\n\
+         fly = KeyF\n\
+         pan_down = KeyK, Hyperspace\n",
+    );
+    assert_eq!(errors.len(), 3);
+    assert!(errors[0].starts_with("line 1:"));
+    assert!(errors[1].starts_with("line 2:"));
+    assert!(errors[2].starts_with("line 3:"));
+    assert_eq!(
+        bindings.get(Action::PanUp),
+        InputBindings::default().get(Action::PanUp)
+    );
+    // The inputs that could be read are kept
+    assert_eq!(bindings.get(Action::PanDown), [Binding::Key(KeyCode::KeyK)]);
+}
@@ -0,0 +1,35 @@
+//! Caste selection when a nest spawns ants
+
+use ant_behaviour::caste::{Caste, CasteSettings};
+
+#[test]
+fn spawned_castes_follow_the_ratios() {
+    let settings = CasteSettings {
+        ratios: [0.5, 0.25, 0.25],
+        ..CasteSettings::default()
+    };
+    let mut counts = [0; 3];
+    for _ in 0..100 {
+        counts[settings.next_caste(counts).index()] += 1;
+    }
+    assert_eq!(counts, [50, 25, 25]);
+}
+
+#[test]
+fn the_caste_furthest_below_its_ratio_comes_next() {
+    let settings = CasteSettings {
+        ratios: [0.5, 0.25, 0.25],
+        ..CasteSettings::default()
+    };
+    assert_eq!(settings.next_caste([10, 0, 5]), Caste::Scout);
+    assert_eq!(settings.next_caste([0, 5, 5]), Caste::Worker);
+}
+
+#[test]
+fn zero_ratios_spawn_workers() {
+    let settings = CasteSettings {
+        ratios: [0.0; 3],
+        ..CasteSettings::default()
+    };
+    assert_eq!(settings.next_caste([3, 1, 2]), Caste::Worker);
+}
@@ -0,0 +1,36 @@
+//! Food regrowth and seasons
+
+use ant_behaviour::food::{FoodSettings, FoodSource, Seasons};
+
+#[test]
+fn sources_grow_back_up_to_their_capacity() {
+    let mut food = FoodSource::new(50.0);
+    food.take(30.0);
+    food.regrow(0.1, 1.0);
+    assert_eq!(food.amount, 25.0);
+    food.regrow(0.1, 100.0);
+    assert_eq!(food.amount, food.capacity);
+}
+
+#[test]
+fn abundance_peaks_in_summer_and_averages_one() {
+    let settings = FoodSettings::default();
+    let mut seasons = Seasons::default();
+    let samples = 400;
+    let mut total = 0.0;
+    let mut summer_peak: f32 = 0.0;
+    let mut winter_low: f32 = f32::MAX;
+    for step in 0..samples {
+        seasons.time_of_year = settings.year_length * step as f32 / samples as f32;
+        let abundance = seasons.abundance(&settings);
+        total += abundance;
+        match seasons.name(&settings) {
+            "summer" => summer_peak = summer_peak.max(abundance),
+            "winter" => winter_low = winter_low.min(abundance),
+            _ => {}
+        }
+    }
+    assert!((total / samples as f32 - 1.0).abs() < 1e-3);
+    assert!(summer_peak > 1.0 + settings.season_amplitude * 0.9);
+    assert!(winter_low < 1.0 - settings.season_amplitude * 0.9);
+}
@@ -0,0 +1,50 @@
+//! Genome mutation and breeding
+
+use ant_behaviour::{
+    genome::{GenePool, Genome},
+    GENOME_MUTATION,
+};
+use rand::{rngs::SmallRng, SeedableRng};
+
+#[test]
+fn mutations_stay_within_the_trait_limits() {
+    let mut rng = SmallRng::seed_from_u64(1);
+    let extreme = Genome {
+        view_angle: 100.0,
+        turn_noise: 0.0,
+        deposit_rate: -1.0,
+        trail_follow_weight: 10.0,
+    };
+    for _ in 0..100 {
+        let child = extreme.mutate(&mut rng);
+        assert_eq!(child.view_angle, 4.0);
+        assert_eq!(child.turn_noise, 0.01);
+        assert_eq!(child.deposit_rate, 0.0);
+        assert_eq!(child.trail_follow_weight, 5.0);
+    }
+}
+
+#[test]
+fn offspring_are_close_to_a_parent_of_the_pool() {
+    let mut rng = SmallRng::seed_from_u64(2);
+    let close = |child: &Genome, parent: &Genome| {
+        (child.view_angle / parent.view_angle - 1.0).abs() <= GENOME_MUTATION + 1e-5
+            && (child.deposit_rate / parent.deposit_rate - 1.0).abs() <= GENOME_MUTATION + 1e-5
+    };
+
+    let empty = GenePool::default();
+    for _ in 0..20 {
+        assert!(close(&empty.offspring(&mut rng), &Genome::default()));
+    }
+
+    let parent = Genome {
+        view_angle: 2.0,
+        deposit_rate: 3.0,
+        ..Genome::default()
+    };
+    let mut pool = GenePool::default();
+    pool.add(&parent);
+    for _ in 0..20 {
+        assert!(close(&pool.offspring(&mut rng), &parent));
+    }
+}
@@ -0,0 +1,118 @@
+//! Screen to world conversion of the cursor at various zoom levels
+
+use ant_behaviour::{utils::window_to_world, CAMERA_MIN_SCALE};
+use bevy::{
+    log::LogPlugin,
+    prelude::*,
+    render::{
+        settings::{RenderCreation, WgpuSettings},
+        RenderPlugin,
+    },
+    window::PrimaryWindow,
+    winit::WinitPlugin,
+};
+
+const ZOOM_LEVELS: [f32; 4] = [CAMERA_MIN_SCALE, 1.0, 2.0, 5.0];
+
+/// Headless app with a 2D camera at `translation`, zoomed by `scale`
+fn camera_app(translation: Vec2, scale: f32) -> App {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(RenderPlugin {
+                render_creation: RenderCreation::Automatic(WgpuSettings {
+                    backends: None,
+                    ..default()
+                }),
+                ..default()
+            })
+            .disable::<WinitPlugin>()
+            .disable::<LogPlugin>(),
+    );
+    app.world_mut().spawn((
+        Camera2d,
+        Transform {
+            translation: translation.extend(0.0),
+            scale: Vec3::new(scale, scale, 1.0),
+            ..default()
+        },
+    ));
+    app.finish();
+    app.cleanup();
+    // Let the camera pick up the window size and its transform propagate
+    app.update();
+    app.update();
+    app
+}
+
+fn camera(app: &mut App) -> (Camera, GlobalTransform, Vec2) {
+    let window_size = app
+        .world_mut()
+        .query_filtered::<&Window, With<PrimaryWindow>>()
+        .single(app.world())
+        .size();
+    let (camera, transform) = app
+        .world_mut()
+        .query_filtered::<(&Camera, &GlobalTransform), With<Camera2d>>()
+        .single(app.world());
+    (camera.clone(), *transform, window_size)
+}
+
+#[test]
+fn window_center_is_camera_position() {
+    for scale in ZOOM_LEVELS {
+        let position = Vec2::new(120.0, -340.0);
+        let mut app = camera_app(position, scale);
+        let (camera, transform, window_size) = camera(&mut app);
+
+        let center = window_to_world(window_size / 2.0, &camera, &transform).unwrap();
+        assert!(
+            center.distance(position) < 1e-3,
+            "zoom {scale}: {center} != {position}"
+        );
+    }
+}
+
+#[test]
+fn window_corners_follow_zoom() {
+    for scale in ZOOM_LEVELS {
+        let mut app = camera_app(Vec2::ZERO, scale);
+        let (camera, transform, window_size) = camera(&mut app);
+
+        // The top left corner of the window is up and to the left in the world
+        let top_left = window_to_world(Vec2::ZERO, &camera, &transform).unwrap();
+        let expected = Vec2::new(-window_size.x, window_size.y) / 2.0 * scale;
+        assert!(
+            top_left.distance(expected) < 1e-2,
+            "zoom {scale}: {top_left} != {expected}"
+        );
+    }
+}
+
+#[test]
+fn picks_the_point_under_the_cursor() {
+    let points = [
+        Vec2::ZERO,
+        Vec2::new(50.0, 25.0),
+        Vec2::new(-90.0, 60.0),
+        Vec2::new(130.0, -70.0),
+    ];
+    for scale in ZOOM_LEVELS {
+        let camera_position = Vec2::new(-500.0, 250.0);
+        let mut app = camera_app(camera_position, scale);
+        let (camera, transform, _) = camera(&mut app);
+
+        for point in points {
+            // Keep the points on screen whatever the zoom
+            let world_pos = camera_position + point * scale;
+            let cursor = camera
+                .world_to_viewport(&transform, world_pos.extend(0.0))
+                .unwrap();
+            let picked = window_to_world(cursor, &camera, &transform).unwrap();
+            assert!(
+                picked.distance(world_pos) < 1e-2,
+                "zoom {scale}: picked {picked} instead of {world_pos}"
+            );
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Scent falloff and how it steers with vision
+
+use ant_behaviour::ant::AntSettings;
+use bevy::math::Vec2;
+
+#[test]
+fn scent_fades_to_nothing_at_the_radius() {
+    let settings = AntSettings {
+        scent_radius: 100.0,
+        scent_falloff: 2.0,
+        ..AntSettings::default()
+    };
+    assert_eq!(settings.scent_strength(0.0), 1.0);
+    assert_eq!(settings.scent_strength(50.0), 0.25);
+    assert_eq!(settings.scent_strength(100.0), 0.0);
+    assert_eq!(settings.scent_strength(150.0), 0.0);
+    assert!(settings.scent_strength(20.0) > settings.scent_strength(40.0));
+}
+
+#[test]
+fn scent_and_vision_are_blended_by_weight() {
+    let settings = AntSettings {
+        scent_weight: 0.5,
+        ..AntSettings::default()
+    };
+    // Either direction alone is followed as is
+    assert_eq!(settings.blend_scent(Vec2::ZERO, Vec2::X * 3.0), Vec2::X);
+    assert_eq!(settings.blend_scent(Vec2::Y * 2.0, Vec2::ZERO), Vec2::Y);
+    // Equal weights point halfway
+    let blended = settings.blend_scent(Vec2::Y, Vec2::X * 10.0);
+    assert!(blended.abs_diff_eq(Vec2::ONE.normalize(), 1e-5));
+}
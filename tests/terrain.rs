@@ -0,0 +1,42 @@
+//! Terrain maps and their effect on the grid
+
+use ant_behaviour::{
+    grid::Grid,
+    terrain::{Terrain, TerrainMap},
+};
+use bevy::prelude::*;
+
+#[test]
+fn maps_are_stretched_over_the_grid_north_first() {
+    let map = TerrainMap::parse(":~\n#%\n").unwrap();
+    let mut grid = Grid::default();
+    map.apply(&mut grid);
+
+    let (min, max) = grid.get_boundaries();
+    assert_eq!(
+        grid.terrain_at(Vec2::new(min.x, max.y) + 1.0),
+        Terrain::Sand
+    );
+    assert_eq!(grid.terrain_at(max - 1.0), Terrain::Water);
+    assert!(grid.is_wall_at(min + 1.0));
+    assert!(!grid.is_wall_at(Vec2::new(max.x, min.y) + Vec2::new(-1.0, 1.0)));
+    assert_eq!(
+        grid.terrain_at(Vec2::new(max.x, min.y) + Vec2::new(-1.0, 1.0)),
+        Terrain::Mud
+    );
+}
+
+#[test]
+fn short_rows_are_grass_and_unknown_symbols_fail() {
+    let map = TerrainMap::parse("~~\n~\n").unwrap();
+    let mut grid = Grid::default();
+    map.apply(&mut grid);
+    let (_, max) = grid.get_boundaries();
+    assert_eq!(
+        grid.terrain_at(Vec2::new(max.x - 1.0, -1.0)),
+        Terrain::Grass
+    );
+
+    assert!(TerrainMap::parse("..\n.x\n").is_err());
+    assert!(TerrainMap::parse("\n\n").is_err());
+}
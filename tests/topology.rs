@@ -0,0 +1,65 @@
+//! Wrapping around the edges of a torus grid
+
+use std::collections::HashSet;
+
+use ant_behaviour::{
+    grid::{Grid, Topology},
+    utils::wrap_delta,
+};
+use bevy::prelude::*;
+
+#[test]
+fn wrapped_deltas_take_the_short_way() {
+    let size = Vec2::splat(10.0);
+    assert_eq!(wrap_delta(Vec2::new(4.0, -4.0), size), Vec2::new(4.0, -4.0));
+    assert_eq!(wrap_delta(Vec2::new(6.0, -6.0), size), Vec2::new(-4.0, 4.0));
+    assert_eq!(
+        wrap_delta(Vec2::new(13.0, -17.0), size),
+        Vec2::new(3.0, 3.0)
+    );
+
+    // Half way around both ways are as short, the length is all that matters
+    assert_eq!(
+        wrap_delta(Vec2::new(5.0, -5.0), size).abs(),
+        Vec2::splat(5.0)
+    );
+}
+
+#[test]
+fn constrain_clamps_or_wraps() {
+    let bounded = Grid::default();
+    let torus = Grid::default().with_topology(Topology::Torus);
+    let (min, max) = bounded.get_boundaries();
+    let outside = Vec2::new(max.x + 10.0, min.y - 30.0);
+
+    assert_eq!(bounded.constrain(outside), Vec2::new(max.x, min.y));
+    assert_eq!(
+        torus.constrain(outside),
+        Vec2::new(min.x + 10.0, max.y - 30.0)
+    );
+    assert_eq!(torus.constrain(Vec2::ZERO), Vec2::ZERO);
+    assert_eq!(torus.constrain(max), min);
+}
+
+#[test]
+fn areas_larger_than_the_grid_list_each_cell_once() {
+    for topology in [Topology::Bounded, Topology::Torus] {
+        let grid = Grid::default().with_topology(topology);
+        let size = grid.get_size();
+        let radius = size.x as f32 * grid.get_cell_size().x * 2.0;
+
+        let cells = grid.get_cells_in_area_from_grid(UVec2::new(1, size.y - 1), radius);
+        let unique: HashSet<_> = cells.iter().collect();
+        assert_eq!(unique.len(), cells.len(), "{topology:?}");
+        assert_eq!(cells.len(), (size.x * size.y) as usize, "{topology:?}");
+
+        let cells: Vec<_> = grid
+            .get_cells_in_area_from_world(Vec2::ZERO, radius)
+            .into_iter()
+            .map(|(pos, _)| pos)
+            .collect();
+        let unique: HashSet<_> = cells.iter().collect();
+        assert_eq!(unique.len(), cells.len(), "{topology:?}");
+        assert_eq!(cells.len(), (size.x * size.y) as usize, "{topology:?}");
+    }
+}
@@ -0,0 +1,72 @@
+//! View cone intersection, partial visibility and occlusion
+
+use ant_behaviour::{grid::Grid, utils::ViewCone};
+use bevy::prelude::*;
+
+/// Cone at the origin looking up, 100 units deep and 90° wide
+fn cone() -> ViewCone {
+    ViewCone::new(Vec2::ZERO, 100.0, 90f32.to_radians(), 0.0)
+}
+
+#[test]
+fn circles_overlapping_the_edges_are_seen() {
+    let cone = cone();
+    // Just outside the view angle, but close enough to an edge to overlap it
+    let beside = Vec2::from_angle(-50f32.to_radians()).rotate(Vec2::Y) * 50.0;
+    assert!(cone.contains(beside, 10.0));
+    assert!(!cone.contains(beside, 2.0));
+
+    // Past the arc
+    assert!(cone.contains(Vec2::new(0.0, 105.0), 10.0));
+    assert!(!cone.contains(Vec2::new(0.0, 115.0), 10.0));
+
+    // Behind, around the apex
+    assert!(cone.contains(Vec2::new(0.0, -5.0), 10.0));
+    assert!(!cone.contains(Vec2::new(0.0, -20.0), 10.0));
+}
+
+#[test]
+fn visible_fraction_measures_the_overlap() {
+    let cone = cone();
+    assert_eq!(cone.visible_fraction(Vec2::new(0.0, 50.0), 10.0), 1.0);
+    assert_eq!(cone.visible_fraction(Vec2::new(0.0, -50.0), 10.0), 0.0);
+
+    // Halfway across the arc and halfway across an edge
+    let on_arc = cone.visible_fraction(Vec2::new(0.0, 100.0), 10.0);
+    assert!((on_arc - 0.5).abs() < 0.1, "{on_arc}");
+    let edge = Vec2::from_angle(-45f32.to_radians()).rotate(Vec2::Y) * 50.0;
+    let on_edge = cone.visible_fraction(edge, 10.0);
+    assert!((on_edge - 0.5).abs() < 0.1, "{on_edge}");
+}
+
+#[test]
+fn closer_circles_hide_what_is_behind() {
+    let cone = cone();
+    let blocker = Vec2::new(0.0, 30.0);
+    assert!(cone.occludes(blocker, 10.0, Vec2::new(0.0, 60.0)));
+    assert!(!cone.occludes(blocker, 10.0, Vec2::new(40.0, 60.0)));
+    // Something farther away hides nothing in front of it
+    assert!(!cone.occludes(Vec2::new(0.0, 60.0), 10.0, blocker));
+}
+
+#[test]
+fn wrapping_cones_see_across_the_edges() {
+    let mut cone = cone();
+    cone.wrap_around(Some(Vec2::splat(1000.0)));
+    assert!(cone.contains(Vec2::new(0.0, -950.0), 0.0));
+    assert!(!cone.contains(Vec2::new(0.0, 950.0), 0.0));
+}
+
+#[test]
+fn walls_block_the_line_of_sight() {
+    let mut grid = Grid::default();
+    let (from, to) = (Vec2::new(-200.0, 0.0), Vec2::new(200.0, 0.0));
+    assert!(grid.line_of_sight(from, to));
+
+    grid.set_wall(grid.get_grid_pos(Vec2::ZERO), true);
+    assert!(!grid.line_of_sight(from, to));
+    assert!(grid.line_of_sight(from + Vec2::Y * 200.0, to + Vec2::Y * 200.0));
+
+    grid.set_wall(grid.get_grid_pos(Vec2::ZERO), false);
+    assert!(grid.line_of_sight(from, to));
+}